@@ -0,0 +1,204 @@
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use anyhow::{bail, Result};
+use heist::economics::TableStats;
+use heist::state::*;
+use heist_client::decode;
+
+pub fn print_account(address: &Pubkey, data: &[u8]) -> Result<()> {
+    if data.len() < 8 {
+        bail!("{} is not a heist account", address);
+    }
+    let discriminator: [u8; 8] = data[..8].try_into()?;
+    println!("Address: {}", address);
+    if discriminator == Global::discriminator() {
+        print_global(&decode::global(data)?);
+    } else if discriminator == PlayerInfo::discriminator() {
+        print_player_info(&decode::player_info(data)?);
+    } else if discriminator == PlayerStakeInfo::discriminator() {
+        print_player_stake_info(&decode::player_stake_info(data)?);
+    } else if discriminator == PendingChange::discriminator() {
+        print_pending_change(&decode::pending_change(data)?);
+    } else if discriminator == Blocklist::discriminator() {
+        print_blocklist(&decode::blocklist(data)?);
+    } else {
+        bail!("{} is not a heist account", address);
+    }
+    Ok(())
+}
+
+// Global is packed, fields are copied out before formatting
+fn print_global(global: &Global) {
+    println!("Type: Global");
+    println!("  Collection: {}", { global.collection });
+    println!("  Reward mint: {}", { global.reward_mint });
+    println!("  End date: {}", { global.end_date });
+    println!("  Total players: {}", { global.total_player });
+    println!("  Update authority: {}", { global.update_authority });
+    println!("  Pending authority: {}", { global.pending_authority });
+    println!("  Operator: {}", { global.operator });
+    println!("  Treasurer: {}", { global.treasurer });
+    println!("  Pauser: {}", { global.pauser });
+    println!("  Paused: {}", global.is_paused());
+    println!("  Next change id: {}", { global.change_nonce });
+    println!("  Change delay: {}s", { global.change_delay });
+    println!(
+        "  RTP bounds: {} - {}",
+        percent(u64::from({ global.min_rtp_bps })),
+        percent(u64::from({ global.max_rtp_bps }))
+    );
+    println!("  Role merkle root: {}", hex(&{ global.role_merkle_root }));
+    println!("  Committed rewards: {}", global.committed_rewards());
+    for bank in { global.banks }.iter() {
+        println!("  Bank {}:", tier_name(bank.bank_tier()));
+        println!("    Reward per hour: {}", { bank.reward_per_hour });
+        println!("    Total staked: {}", { bank.total_staked });
+        println!("    Total weight: {}", bank.total_weight());
+        let roles = [RoleType::Chimp, RoleType::Gorrila]
+            .into_iter()
+            .filter(|&role| bank.allows(role))
+            .map(role_name)
+            .collect::<Vec<_>>();
+        println!("    Allowed roles: {}", roles.join(", "));
+        let stats = TableStats::from_bank(bank);
+        println!("    RTP: {}", percent(stats.rtp_bps()));
+        println!("    Ruin: {}", percent(stats.ruin_probability_bps()));
+        for outcome in bank.outcomes() {
+            println!(
+                "    Outcome: odds {} x{} {}",
+                { outcome.odds },
+                { outcome.payout_multiplier },
+                negative_name(outcome.negative_outcome())
+            );
+        }
+    }
+    for role in { global.roles }.iter() {
+        println!("  Role {}:", role_name(role.role_type()));
+        println!("    Reward boost: {} bps", { role.reward_boost_bps });
+        println!("    Odds shift: {}", { role.odds_shift });
+        println!("    Jail time reduction: {}s", { role.jail_time_reduction });
+    }
+}
+
+fn print_player_info(player_info: &PlayerInfo) {
+    println!("Type: PlayerInfo");
+    println!("  Point balance: {}", player_info.point_balance);
+    println!("  Active staked: {}", player_info.active_staked);
+    println!("  Jail end time: {}", player_info.jail_end_time);
+}
+
+fn print_player_stake_info(stake: &PlayerStakeInfo) {
+    println!("Type: PlayerStakeInfo");
+    println!("  Owner: {}", stake.owner);
+    println!("  Mint: {}", stake.mint);
+    println!("  Bank: {}", tier_name(stake.bank));
+    println!("  Stake start time: {}", stake.stake_start_time);
+    println!("  Role: {}", role_name(stake.role));
+    println!("  Rarity: {}", stake.rarity);
+    println!("  Compressed: {}", stake.is_compressed);
+}
+
+fn print_pending_change(pending_change: &PendingChange) {
+    println!("Type: PendingChange");
+    println!("  Id: {}", pending_change.id);
+    println!("  Queued at: {}", pending_change.queued_at);
+    println!(
+        "  Earliest execution time: {}",
+        pending_change.earliest_execution_time
+    );
+    match &pending_change.change {
+        ConfigChange::UpdateBank {
+            bank_tier_risk,
+            reward_per_hour,
+            bank_outcomes,
+        } => {
+            println!("  Change: update bank {}", tier_name(*bank_tier_risk));
+            println!("    Reward per hour: {}", reward_per_hour);
+            for outcome in bank_outcomes {
+                println!(
+                    "    Outcome: odds {} x{} {}",
+                    outcome.odds,
+                    outcome.payout_multiplier,
+                    negative_name(outcome.negative_outcome())
+                );
+            }
+        }
+        ConfigChange::UpdateBankRoles {
+            bank_tier_risk,
+            allowed_roles,
+        } => {
+            let roles = allowed_roles
+                .iter()
+                .map(|&role| role_name(role))
+                .collect::<Vec<_>>();
+            println!(
+                "  Change: allow {} in bank {}",
+                roles.join(", "),
+                tier_name(*bank_tier_risk)
+            );
+        }
+        ConfigChange::UpdateRole {
+            role_type,
+            reward_boost_bps,
+            odds_shift,
+            jail_time_reduction,
+        } => {
+            println!("  Change: update role {}", role_name(*role_type));
+            println!("    Reward boost: {} bps", reward_boost_bps);
+            println!("    Odds shift: {}", odds_shift);
+            println!("    Jail time reduction: {}s", jail_time_reduction);
+        }
+        ConfigChange::UpdateChangeDelay { change_delay } => {
+            println!("  Change: change delay to {}s", change_delay);
+        }
+        ConfigChange::UpdateRtpBounds {
+            min_rtp_bps,
+            max_rtp_bps,
+        } => {
+            println!(
+                "  Change: RTP bounds to {} - {}",
+                percent(u64::from(*min_rtp_bps)),
+                percent(u64::from(*max_rtp_bps))
+            );
+        }
+    }
+}
+
+fn print_blocklist(blocklist: &Blocklist) {
+    println!("Type: Blocklist");
+    for wallet in &blocklist.wallets {
+        println!("  {}", wallet);
+    }
+}
+
+fn tier_name(tier: BankTierRisk) -> &'static str {
+    match tier {
+        BankTierRisk::Low => "Low",
+        BankTierRisk::Mid => "Mid",
+        BankTierRisk::High => "High",
+    }
+}
+
+fn role_name(role: RoleType) -> &'static str {
+    match role {
+        RoleType::Chimp => "Chimp",
+        RoleType::Gorrila => "Gorrila",
+    }
+}
+
+fn negative_name(outcome: NegativeOutcome) -> &'static str {
+    match outcome {
+        NegativeOutcome::None => "",
+        NegativeOutcome::Fumbled => "Fumbled",
+        NegativeOutcome::Confiscation => "Confiscation",
+        NegativeOutcome::Arrested => "Arrested",
+        NegativeOutcome::Rekt => "Rekt",
+    }
+}
+
+fn percent(bps: u64) -> String {
+    format!("{}.{:02}%", bps / 100, bps % 100)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,291 @@
+mod inspect;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{associated_token::get_associated_token_address, token::spl_token};
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use heist_client::{decode, instructions, pda::*};
+use heist_config::Config;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    instruction::Instruction,
+    message::Message,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(name = "heist-cli", about = "Manage a heist pool")]
+struct Cli {
+    #[clap(long, default_value = "http://localhost:8899")]
+    url: String,
+    /// Defaults to ~/.config/solana/id.json
+    #[clap(long)]
+    keypair: Option<PathBuf>,
+    /// Print the unsigned transaction as base64 instead of signing and sending it
+    #[clap(long)]
+    dump_transaction: bool,
+    /// Signing key when dumping for a hardware wallet, no keypair is read
+    #[clap(long, requires = "dump_transaction")]
+    signer: Option<Pubkey>,
+    /// Skips the RPC lookup so the transaction can be built fully offline
+    #[clap(long)]
+    blockhash: Option<Hash>,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the pool, or push its end date later and top up rewards
+    InitPool {
+        #[clap(long)]
+        user_nft_account: Pubkey,
+        #[clap(long)]
+        user_nft_mint: Pubkey,
+        #[clap(long)]
+        collection_mint: Pubkey,
+        #[clap(long)]
+        reward_mint: Pubkey,
+        /// Unix timestamp, defaults to schedule.end_date in --config
+        #[clap(long)]
+        end_date: Option<u64>,
+        /// Defaults to pool.reward_per_hour in --config
+        #[clap(long)]
+        reward_per_hour: Option<u64>,
+        #[clap(long)]
+        config: Option<PathBuf>,
+    },
+    /// Queue the banks, roles and change delay from a TOML or JSON config behind the timelock
+    ConfigureBanks {
+        #[clap(long)]
+        config: PathBuf,
+        /// Id of the first queued change, read from the pool when omitted.
+        /// Required with --blockhash, the pool can't be read offline
+        #[clap(long)]
+        change_id: Option<u64>,
+    },
+    ExecuteChange {
+        #[clap(long)]
+        change_id: u64,
+        #[clap(long)]
+        reward_mint: Pubkey,
+    },
+    CancelChange {
+        #[clap(long)]
+        change_id: u64,
+    },
+    UpdatePermissions {
+        #[clap(long)]
+        operator: Pubkey,
+        #[clap(long)]
+        treasurer: Pubkey,
+        #[clap(long)]
+        pauser: Pubkey,
+    },
+    ProposeAuthority {
+        #[clap(long)]
+        new_authority: Pubkey,
+    },
+    AcceptAuthority,
+    CancelAuthorityTransfer,
+    /// Move reward tokens from the signer into the pool vault
+    Fund {
+        #[clap(long)]
+        reward_mint: Pubkey,
+        #[clap(long)]
+        amount: u64,
+    },
+    Withdraw {
+        #[clap(long)]
+        reward_mint: Pubkey,
+        #[clap(long)]
+        destination: Pubkey,
+        #[clap(long)]
+        amount: u64,
+    },
+    Pause,
+    Unpause,
+    /// Print any heist account, the pool when no address is given, no keypair needed
+    Inspect {
+        address: Option<Pubkey>,
+    },
+}
+
+struct Context {
+    rpc: RpcClient,
+    signer: Pubkey,
+    keypair: Option<Keypair>,
+    dump_transaction: bool,
+    blockhash: Option<Hash>,
+}
+
+fn rpc_client(cli: &Cli) -> RpcClient {
+    RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed())
+}
+
+impl Context {
+    fn new(cli: &Cli) -> Result<Context> {
+        let rpc = rpc_client(cli);
+        if let Some(signer) = cli.signer {
+            return Ok(Context {
+                rpc,
+                signer,
+                keypair: None,
+                dump_transaction: cli.dump_transaction,
+                blockhash: cli.blockhash,
+            });
+        }
+
+        let path = match &cli.keypair {
+            Some(path) => path.clone(),
+            None => PathBuf::from(std::env::var("HOME")?).join(".config/solana/id.json"),
+        };
+        let keypair = read_keypair_file(&path)
+            .map_err(|err| anyhow!("reading keypair {}: {}", path.display(), err))?;
+        Ok(Context {
+            rpc,
+            signer: keypair.pubkey(),
+            keypair: Some(keypair),
+            dump_transaction: cli.dump_transaction,
+            blockhash: cli.blockhash,
+        })
+    }
+
+    fn submit(&self, instructions: &[Instruction]) -> Result<()> {
+        let blockhash = match self.blockhash {
+            Some(blockhash) => blockhash,
+            None => self.rpc.get_latest_blockhash()?,
+        };
+        let message = Message::new_with_blockhash(instructions, Some(&self.signer), &blockhash);
+
+        if self.dump_transaction {
+            let transaction = Transaction::new_unsigned(message);
+            println!("{}", base64::encode(bincode::serialize(&transaction)?));
+            return Ok(());
+        }
+
+        let keypair = self
+            .keypair
+            .as_ref()
+            .ok_or_else(|| anyhow!("a keypair is required to send transactions"))?;
+        let transaction = Transaction::new(&[keypair], message, blockhash);
+        let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+        println!("Signature: {}", signature);
+        Ok(())
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    // Read-only, so it runs without a keypair or --signer
+    if let Command::Inspect { address } = cli.command {
+        let address = address.unwrap_or_else(|| find_global_address().0);
+        let data = rpc_client(&cli).get_account_data(&address)?;
+        return inspect::print_account(&address, &data);
+    }
+    let ctx = Context::new(&cli)?;
+    let signer = ctx.signer;
+
+    match cli.command {
+        Command::InitPool {
+            user_nft_account,
+            user_nft_mint,
+            collection_mint,
+            reward_mint,
+            end_date,
+            reward_per_hour,
+            config,
+        } => {
+            let config = config.map(|path| Config::load(&path)).transpose()?;
+            let end_date = end_date
+                .or_else(|| config.as_ref()?.schedule.end_date)
+                .ok_or_else(|| {
+                    anyhow!("--end-date or schedule.end_date in --config is required")
+                })?;
+            let reward_per_hour = reward_per_hour
+                .or_else(|| Some(config.as_ref()?.pool.reward_per_hour))
+                .ok_or_else(|| anyhow!("--reward-per-hour or --config is required"))?;
+            ctx.submit(&[instructions::init_or_update_collection(
+                &signer,
+                &user_nft_account,
+                &user_nft_mint,
+                &collection_mint,
+                &reward_mint,
+                end_date,
+                reward_per_hour,
+            )])
+        }
+        Command::ConfigureBanks { config, change_id } => {
+            let changes = Config::load(&config)?.to_changes();
+            let first_id = match change_id {
+                Some(change_id) => change_id,
+                None if ctx.blockhash.is_some() => {
+                    return Err(anyhow!("--change-id is required with --blockhash"));
+                }
+                None => {
+                    let data = ctx.rpc.get_account_data(&find_global_address().0)?;
+                    decode::global(&data)?.change_nonce
+                }
+            };
+            // Each change opens its own account, queue them one per transaction
+            for (change_id, change) in (first_id..).zip(changes) {
+                println!("Queueing change {}", change_id);
+                ctx.submit(&[instructions::queue_change(&signer, change_id, change)])?;
+            }
+            Ok(())
+        }
+        Command::ExecuteChange {
+            change_id,
+            reward_mint,
+        } => ctx.submit(&[instructions::execute_change(
+            &signer,
+            change_id,
+            &reward_mint,
+        )]),
+        Command::CancelChange { change_id } => {
+            ctx.submit(&[instructions::cancel_change(&signer, change_id)])
+        }
+        Command::UpdatePermissions {
+            operator,
+            treasurer,
+            pauser,
+        } => ctx.submit(&[instructions::update_permissions(
+            &signer, operator, treasurer, pauser,
+        )]),
+        Command::ProposeAuthority { new_authority } => {
+            ctx.submit(&[instructions::propose_authority(&signer, &new_authority)])
+        }
+        Command::AcceptAuthority => ctx.submit(&[instructions::accept_authority(&signer)]),
+        Command::CancelAuthorityTransfer => {
+            ctx.submit(&[instructions::cancel_authority_transfer(&signer)])
+        }
+        Command::Fund {
+            reward_mint,
+            amount,
+        } => ctx.submit(&[spl_token::instruction::transfer(
+            &spl_token::ID,
+            &get_associated_token_address(&signer, &reward_mint),
+            &get_global_reward_token_address(&reward_mint),
+            &signer,
+            &[],
+            amount,
+        )?]),
+        Command::Withdraw {
+            reward_mint,
+            destination,
+            amount,
+        } => ctx.submit(&[instructions::withdraw_treasury(
+            &signer,
+            &reward_mint,
+            &destination,
+            amount,
+        )]),
+        Command::Pause => ctx.submit(&[instructions::pause(&signer)]),
+        Command::Unpause => ctx.submit(&[instructions::unpause(&signer)]),
+        Command::Inspect { .. } => unreachable!("inspect runs before the context is built"),
+    }
+}
@@ -0,0 +1,40 @@
+use anchor_lang::{error::ErrorCode, prelude::*, Discriminator};
+use heist::state::{Blocklist, Global, PendingChange, PlayerInfo, PlayerStakeInfo, UnstakePreview};
+
+// Global is zero copy, the bytes after the discriminator are the struct itself
+pub fn global(data: &[u8]) -> Result<Global> {
+    if data.len() < Global::len() {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    if data[..8] != Global::discriminator() {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    let global: Global = bytemuck::pod_read_unaligned(&data[8..Global::len()]);
+    // Flags and enums are raw bytes, reject values the program never writes
+    if !global.is_well_formed() {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    Ok(global)
+}
+
+pub fn player_info(data: &[u8]) -> Result<PlayerInfo> {
+    PlayerInfo::try_deserialize(&mut &data[..])
+}
+
+pub fn player_stake_info(data: &[u8]) -> Result<PlayerStakeInfo> {
+    PlayerStakeInfo::try_deserialize(&mut &data[..])
+}
+
+pub fn blocklist(data: &[u8]) -> Result<Blocklist> {
+    Blocklist::try_deserialize(&mut &data[..])
+}
+
+pub fn pending_change(data: &[u8]) -> Result<PendingChange> {
+    PendingChange::try_deserialize(&mut &data[..])
+}
+
+// Return data of a simulated preview_unstake
+pub fn unstake_preview(return_data: &[u8]) -> Result<UnstakePreview> {
+    UnstakePreview::try_from_slice(return_data)
+        .map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+}
@@ -0,0 +1,74 @@
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use heist::state::{BankTierRisk, PendingChange, PlayerInfo, PlayerStakeInfo, RoleType};
+use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
+
+// PlayerStakeInfo field offsets, discriminator first
+pub const STAKE_OWNER_OFFSET: usize = 8;
+pub const STAKE_MINT_OFFSET: usize = STAKE_OWNER_OFFSET + 32;
+pub const STAKE_BANK_OFFSET: usize = STAKE_MINT_OFFSET + 32;
+pub const STAKE_START_TIME_OFFSET: usize = STAKE_BANK_OFFSET + 1;
+pub const STAKE_ROLE_OFFSET: usize = STAKE_START_TIME_OFFSET + 8;
+pub const STAKE_IS_COMPRESSED_OFFSET: usize = STAKE_ROLE_OFFSET + 1;
+
+fn memcmp(offset: usize, bytes: &[u8]) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp {
+        offset,
+        bytes: MemcmpEncodedBytes::Bytes(bytes.to_vec()),
+        encoding: None,
+    })
+}
+
+pub fn player_infos() -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(PlayerInfo::len() as u64),
+        memcmp(0, &PlayerInfo::discriminator()),
+    ]
+}
+
+// Pending changes vary in size with the queued change
+pub fn pending_changes() -> Vec<RpcFilterType> {
+    vec![memcmp(0, &PendingChange::discriminator())]
+}
+
+pub fn stakes() -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(PlayerStakeInfo::len() as u64),
+        memcmp(0, &PlayerStakeInfo::discriminator()),
+    ]
+}
+
+pub fn stakes_by_owner(owner: &Pubkey) -> Vec<RpcFilterType> {
+    let mut filters = stakes();
+    filters.push(memcmp(STAKE_OWNER_OFFSET, owner.as_ref()));
+    filters
+}
+
+pub fn stakes_by_mint(mint: &Pubkey) -> Vec<RpcFilterType> {
+    let mut filters = stakes();
+    filters.push(memcmp(STAKE_MINT_OFFSET, mint.as_ref()));
+    filters
+}
+
+pub fn stakes_by_bank(bank: BankTierRisk) -> Vec<RpcFilterType> {
+    let mut filters = stakes();
+    filters.push(memcmp(STAKE_BANK_OFFSET, &[bank as u8]));
+    filters
+}
+
+pub fn stakes_by_owner_and_bank(owner: &Pubkey, bank: BankTierRisk) -> Vec<RpcFilterType> {
+    let mut filters = stakes_by_owner(owner);
+    filters.push(memcmp(STAKE_BANK_OFFSET, &[bank as u8]));
+    filters
+}
+
+pub fn stakes_by_role(role: RoleType) -> Vec<RpcFilterType> {
+    let mut filters = stakes();
+    filters.push(memcmp(STAKE_ROLE_OFFSET, &[role as u8]));
+    filters
+}
+
+pub fn stakes_by_compression(is_compressed: bool) -> Vec<RpcFilterType> {
+    let mut filters = stakes();
+    filters.push(memcmp(STAKE_IS_COMPRESSED_OFFSET, &[is_compressed as u8]));
+    filters
+}
@@ -0,0 +1,546 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, sysvar},
+    system_program, Id, InstructionData,
+};
+use anchor_spl::{associated_token, token};
+use heist::{
+    accounts, instruction,
+    state::{BankTierRisk, ConfigChange, RoleProof},
+};
+use mpl_bubblegum::state::metaplex_adapter::MetadataArgs;
+use spl_account_compression::Noop;
+
+use crate::pda::*;
+
+// Leaf location and proof for compressed NFT instructions
+pub struct CompressedLeaf {
+    pub merkle_tree: Pubkey,
+    pub nonce: u64,
+    pub index: u32,
+    pub root: [u8; 32],
+    // Proof nodes, passed as remaining accounts
+    pub proof: Vec<Pubkey>,
+}
+
+impl CompressedLeaf {
+    pub fn asset_id(&self) -> Pubkey {
+        get_asset_id(&self.merkle_tree, self.nonce)
+    }
+
+    fn proof_metas(&self) -> Vec<AccountMeta> {
+        self.proof
+            .iter()
+            .map(|node| AccountMeta::new_readonly(*node, false))
+            .collect()
+    }
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: heist::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn init_or_update_collection(
+    creator: &Pubkey,
+    user_nft_account: &Pubkey,
+    user_nft_mint: &Pubkey,
+    collection_mint: &Pubkey,
+    reward_mint: &Pubkey,
+    end_date: u64,
+    reward_per_hour: u64,
+) -> Instruction {
+    build(
+        accounts::InitOrUpdateCollection {
+            global_state: find_global_address().0,
+            blocklist: find_blocklist_address().0,
+            creator: *creator,
+            user_nft_account: *user_nft_account,
+            nft_metadata: find_metadata_address(user_nft_mint).0,
+            user_nft_mint: *user_nft_mint,
+            collection_mint: *collection_mint,
+            creator_token_reward_account: get_player_reward_token_address(creator, reward_mint),
+            reward_mint: *reward_mint,
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::InitOrUpdateCollection {
+            end_date,
+            reward_per_hour,
+        },
+    )
+}
+
+pub fn update_permissions(
+    update_authority: &Pubkey,
+    operator: Pubkey,
+    treasurer: Pubkey,
+    pauser: Pubkey,
+) -> Instruction {
+    build(
+        accounts::UpdatePermissions {
+            global_state: find_global_address().0,
+            update_authority: *update_authority,
+        },
+        instruction::UpdatePermissions {
+            operator,
+            treasurer,
+            pauser,
+        },
+    )
+}
+
+pub fn withdraw_treasury(
+    treasurer: &Pubkey,
+    reward_mint: &Pubkey,
+    destination_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::WithdrawTreasury {
+            global_state: find_global_address().0,
+            treasurer: *treasurer,
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            destination_token_account: *destination_token_account,
+            token_program: token::ID,
+        },
+        instruction::WithdrawTreasury { amount },
+    )
+}
+
+pub fn queue_change(operator: &Pubkey, change_id: u64, change: ConfigChange) -> Instruction {
+    build(
+        accounts::QueueChange {
+            global_state: find_global_address().0,
+            pending_change: find_pending_change_address(change_id).0,
+            operator: *operator,
+            system_program: system_program::ID,
+        },
+        instruction::QueueChange { change_id, change },
+    )
+}
+
+pub fn execute_change(operator: &Pubkey, change_id: u64, reward_mint: &Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteChange {
+            global_state: find_global_address().0,
+            pending_change: find_pending_change_address(change_id).0,
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            operator: *operator,
+        },
+        instruction::ExecuteChange {},
+    )
+}
+
+pub fn cancel_change(update_authority: &Pubkey, change_id: u64) -> Instruction {
+    build(
+        accounts::CancelChange {
+            global_state: find_global_address().0,
+            pending_change: find_pending_change_address(change_id).0,
+            update_authority: *update_authority,
+        },
+        instruction::CancelChange {},
+    )
+}
+
+pub fn publish_role_merkle_root(operator: &Pubkey, role_merkle_root: [u8; 32]) -> Instruction {
+    build(
+        accounts::PublishRoleMerkleRoot {
+            global_state: find_global_address().0,
+            operator: *operator,
+        },
+        instruction::PublishRoleMerkleRoot { role_merkle_root },
+    )
+}
+
+pub fn propose_authority(current_authority: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::ProposeAuthority {
+            global_state: find_global_address().0,
+            current_authority: *current_authority,
+            new_authority: *new_authority,
+        },
+        instruction::ProposeAuthority {},
+    )
+}
+
+pub fn accept_authority(new_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAuthority {
+            global_state: find_global_address().0,
+            new_authority: *new_authority,
+        },
+        instruction::AcceptAuthority {},
+    )
+}
+
+pub fn cancel_authority_transfer(current_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelAuthorityTransfer {
+            global_state: find_global_address().0,
+            current_authority: *current_authority,
+        },
+        instruction::CancelAuthorityTransfer {},
+    )
+}
+
+pub fn stake_player_stake_info(
+    player: &Pubkey,
+    user_nft_account: &Pubkey,
+    user_nft_mint: &Pubkey,
+    collection_mint: &Pubkey,
+    reward_mint: &Pubkey,
+    bank_tier_risk: BankTierRisk,
+    role_proof: RoleProof,
+) -> Instruction {
+    build(
+        accounts::StakePlayerStakeInfo {
+            global_state: find_global_address().0,
+            blocklist: find_blocklist_address().0,
+            player_info: find_player_info_address(player).0,
+            player_stake_info: find_player_stake_info_address(player, user_nft_mint).0,
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            user_nft_account: *user_nft_account,
+            nft_metadata: find_metadata_address(user_nft_mint).0,
+            user_nft_mint: *user_nft_mint,
+            collection_mint: *collection_mint,
+            player: *player,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::StakePlayerStakeInfo {
+            bank_tier_risk,
+            role_proof,
+        },
+    )
+}
+
+pub fn unstake_player_stake_info(
+    player: &Pubkey,
+    user_nft_account: &Pubkey,
+    nft_mint: &Pubkey,
+    collection_mint: &Pubkey,
+    reward_mint: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::UnstakePlayerStakeInfo {
+            global_state: find_global_address().0,
+            player_info: find_player_info_address(player).0,
+            player_stake_info: find_player_stake_info_address(player, nft_mint).0,
+            player_reward_token_account: get_player_reward_token_address(player, reward_mint),
+            reward_mint: *reward_mint,
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            player: *player,
+            user_nft_account: *user_nft_account,
+            nft_metadata: find_metadata_address(nft_mint).0,
+            nft_mint: *nft_mint,
+            collection_mint: *collection_mint,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::UnstakePlayerStakeInfo {},
+    )
+}
+
+pub fn stake_compressed_player_stake_info(
+    player: &Pubkey,
+    reward_mint: &Pubkey,
+    leaf: &CompressedLeaf,
+    bank_tier_risk: BankTierRisk,
+    metadata: MetadataArgs,
+    role_proof: RoleProof,
+) -> Instruction {
+    let mut ix = build(
+        accounts::StakeCompressedPlayerStakeInfo {
+            global_state: find_global_address().0,
+            blocklist: find_blocklist_address().0,
+            player_info: find_player_info_address(player).0,
+            player_stake_info: find_player_stake_info_address(player, &leaf.asset_id()).0,
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            tree_authority: find_tree_authority_address(&leaf.merkle_tree).0,
+            merkle_tree: leaf.merkle_tree,
+            player: *player,
+            bubblegum_program: mpl_bubblegum::ID,
+            compression_program: spl_account_compression::ID,
+            log_wrapper: Noop::id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::StakeCompressedPlayerStakeInfo {
+            nonce: leaf.nonce,
+            index: leaf.index,
+            root: leaf.root,
+            bank_tier_risk,
+            metadata,
+            role_proof,
+        },
+    );
+    ix.accounts.extend(leaf.proof_metas());
+    ix
+}
+
+pub fn unstake_compressed_player_stake_info(
+    player: &Pubkey,
+    reward_mint: &Pubkey,
+    leaf: &CompressedLeaf,
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+) -> Instruction {
+    let mut ix = build(
+        accounts::UnstakeCompressedPlayerStakeInfo {
+            global_state: find_global_address().0,
+            player_info: find_player_info_address(player).0,
+            player_stake_info: find_player_stake_info_address(player, &leaf.asset_id()).0,
+            player_reward_token_account: get_player_reward_token_address(player, reward_mint),
+            reward_mint: *reward_mint,
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            tree_authority: find_tree_authority_address(&leaf.merkle_tree).0,
+            merkle_tree: leaf.merkle_tree,
+            player: *player,
+            bubblegum_program: mpl_bubblegum::ID,
+            compression_program: spl_account_compression::ID,
+            log_wrapper: Noop::id(),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::UnstakeCompressedPlayerStakeInfo {
+            nonce: leaf.nonce,
+            index: leaf.index,
+            root: leaf.root,
+            data_hash,
+            creator_hash,
+        },
+    );
+    ix.accounts.extend(leaf.proof_metas());
+    ix
+}
+
+pub fn crank_stale_stake(
+    cranker: &Pubkey,
+    owner: &Pubkey,
+    nft_mint: &Pubkey,
+    nft_holder_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::CrankStaleStake {
+            global_state: find_global_address().0,
+            player_info: find_player_info_address(owner).0,
+            player_stake_info: find_player_stake_info_address(owner, nft_mint).0,
+            nft_holder_account: *nft_holder_account,
+            nft_mint: *nft_mint,
+            cranker: *cranker,
+        },
+        instruction::CrankStaleStake {},
+    )
+}
+
+pub fn pause(pauser: &Pubkey) -> Instruction {
+    build(
+        accounts::Pause {
+            global_state: find_global_address().0,
+            pauser: *pauser,
+        },
+        instruction::Pause {},
+    )
+}
+
+pub fn unpause(pauser: &Pubkey) -> Instruction {
+    build(
+        accounts::Pause {
+            global_state: find_global_address().0,
+            pauser: *pauser,
+        },
+        instruction::Unpause {},
+    )
+}
+
+pub fn emergency_unstake(
+    player: &Pubkey,
+    nft_mint: &Pubkey,
+    user_nft_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::EmergencyUnstake {
+            global_state: find_global_address().0,
+            player_info: find_player_info_address(player).0,
+            player_stake_info: find_player_stake_info_address(player, nft_mint).0,
+            user_nft_account: *user_nft_account,
+            player: *player,
+            token_program: token::ID,
+        },
+        instruction::EmergencyUnstake {},
+    )
+}
+
+pub fn emergency_unstake_compressed(
+    player: &Pubkey,
+    leaf: &CompressedLeaf,
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+) -> Instruction {
+    let mut ix = build(
+        accounts::EmergencyUnstakeCompressed {
+            global_state: find_global_address().0,
+            player_info: find_player_info_address(player).0,
+            player_stake_info: find_player_stake_info_address(player, &leaf.asset_id()).0,
+            tree_authority: find_tree_authority_address(&leaf.merkle_tree).0,
+            merkle_tree: leaf.merkle_tree,
+            player: *player,
+            bubblegum_program: mpl_bubblegum::ID,
+            compression_program: spl_account_compression::ID,
+            log_wrapper: Noop::id(),
+            system_program: system_program::ID,
+        },
+        instruction::EmergencyUnstakeCompressed {
+            nonce: leaf.nonce,
+            index: leaf.index,
+            root: leaf.root,
+            data_hash,
+            creator_hash,
+        },
+    );
+    ix.accounts.extend(leaf.proof_metas());
+    ix
+}
+
+// Simulate it and decode the return data with decode::unstake_preview
+pub fn preview_unstake(owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    build(
+        accounts::PreviewUnstake {
+            global_state: find_global_address().0,
+            player_stake_info: find_player_stake_info_address(owner, mint).0,
+        },
+        instruction::PreviewUnstake {},
+    )
+}
+
+pub fn block_player(update_authority: &Pubkey, player: Pubkey) -> Instruction {
+    build(
+        accounts::UpdateBlocklist {
+            global_state: find_global_address().0,
+            blocklist: find_blocklist_address().0,
+            update_authority: *update_authority,
+        },
+        instruction::BlockPlayer { player },
+    )
+}
+
+pub fn unblock_player(update_authority: &Pubkey, player: Pubkey) -> Instruction {
+    build(
+        accounts::UpdateBlocklist {
+            global_state: find_global_address().0,
+            blocklist: find_blocklist_address().0,
+            update_authority: *update_authority,
+        },
+        instruction::UnblockPlayer { player },
+    )
+}
+
+pub fn force_unstake(
+    update_authority: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    user_nft_account: &Pubkey,
+    reward_mint: &Pubkey,
+    forfeit_rewards: bool,
+) -> Instruction {
+    let player_stake_info = find_player_stake_info_address(owner, mint).0;
+    build(
+        accounts::ForceUnstake {
+            global_state: find_global_address().0,
+            player_info: find_player_info_address(owner).0,
+            player_stake_info,
+            owner: *owner,
+            user_nft_account: *user_nft_account,
+            nft_mint: *mint,
+            escrow_nft_account: associated_token::get_associated_token_address(
+                &player_stake_info,
+                mint,
+            ),
+            player_reward_token_account: get_player_reward_token_address(owner, reward_mint),
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            update_authority: *update_authority,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::ForceUnstake { forfeit_rewards },
+    )
+}
+
+pub fn force_unstake_compressed(
+    update_authority: &Pubkey,
+    owner: &Pubkey,
+    reward_mint: &Pubkey,
+    leaf: &CompressedLeaf,
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    forfeit_rewards: bool,
+) -> Instruction {
+    let mut ix = build(
+        accounts::ForceUnstakeCompressed {
+            global_state: find_global_address().0,
+            player_info: find_player_info_address(owner).0,
+            player_stake_info: find_player_stake_info_address(owner, &leaf.asset_id()).0,
+            owner: *owner,
+            player_reward_token_account: get_player_reward_token_address(owner, reward_mint),
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            tree_authority: find_tree_authority_address(&leaf.merkle_tree).0,
+            merkle_tree: leaf.merkle_tree,
+            update_authority: *update_authority,
+            bubblegum_program: mpl_bubblegum::ID,
+            compression_program: spl_account_compression::ID,
+            log_wrapper: Noop::id(),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ForceUnstakeCompressed {
+            nonce: leaf.nonce,
+            index: leaf.index,
+            root: leaf.root,
+            data_hash,
+            creator_hash,
+            forfeit_rewards,
+        },
+    );
+    ix.accounts.extend(leaf.proof_metas());
+    ix
+}
+
+pub fn migrate_stake_info(
+    player: &Pubkey,
+    user_nft_account: &Pubkey,
+    nft_mint: &Pubkey,
+    reward_mint: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::MigrateStakeInfo {
+            global_state: find_global_address().0,
+            legacy_player_stake_info: find_legacy_player_stake_info_address(
+                player,
+                user_nft_account,
+            )
+            .0,
+            player_stake_info: find_player_stake_info_address(player, nft_mint).0,
+            global_reward_token_account: get_global_reward_token_address(reward_mint),
+            user_nft_account: *user_nft_account,
+            player: *player,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::MigrateStakeInfo {},
+    )
+}
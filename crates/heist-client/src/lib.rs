@@ -0,0 +1,7 @@
+pub mod decode;
+pub mod filters;
+pub mod instructions;
+pub mod pda;
+
+pub use heist::state;
+pub use heist::ID as PROGRAM_ID;
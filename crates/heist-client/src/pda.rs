@@ -0,0 +1,23 @@
+pub use heist::pda::*;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+
+// Seeds: ["metadata", token_metadata_program, mint]
+pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    mpl_token_metadata::pda::find_metadata_account(mint)
+}
+
+// Seeds: [merkle_tree] under Bubblegum
+pub fn find_tree_authority_address(merkle_tree: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &mpl_bubblegum::ID)
+}
+
+// Reward vault is the global PDA's associated token account
+pub fn get_global_reward_token_address(reward_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(&find_global_address().0, reward_mint)
+}
+
+pub fn get_player_reward_token_address(player: &Pubkey, reward_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(player, reward_mint)
+}
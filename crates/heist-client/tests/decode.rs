@@ -0,0 +1,116 @@
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use heist::state::{BankTierRisk, Global, NegativeOutcome, RoleType, MAX_BANK_OUTCOMES};
+use heist_client::decode;
+
+// Global field offsets, laid out by hand from the packed struct
+const COLLECTION: usize = 8;
+const END_DATE: usize = 48;
+const BANKS: usize = 88;
+const BANK_HEADER_LEN: usize = 67;
+const BANK_LEN: usize = BANK_HEADER_LEN + MAX_BANK_OUTCOMES * OUTCOME_LEN;
+const OUTCOME_LEN: usize = 7;
+const ROLES: usize = BANKS + 3 * BANK_LEN;
+const ROLE_LEN: usize = 15;
+const IS_INITIALIZED: usize = ROLES + 2 * ROLE_LEN;
+const UPDATE_AUTHORITY: usize = IS_INITIALIZED + 1;
+const CHANGE_DELAY: usize = 864;
+const IS_PAUSED: usize = 872;
+const MAX_RTP_BPS: usize = 877;
+
+fn blank_global() -> Vec<u8> {
+    let mut data = vec![0; Global::len()];
+    data[..8].copy_from_slice(&Global::discriminator());
+    data
+}
+
+fn write(data: &mut [u8], offset: usize, bytes: &[u8]) {
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+#[test]
+fn global_len_is_the_packed_layout() {
+    assert_eq!(BANK_LEN, 179);
+    assert_eq!(Global::len(), MAX_RTP_BPS + 4);
+}
+
+#[test]
+fn global_fields_decode_from_their_offsets() {
+    let collection = Pubkey::new_unique();
+    let update_authority = Pubkey::new_unique();
+    let mut data = blank_global();
+    write(&mut data, COLLECTION, collection.as_ref());
+    write(&mut data, END_DATE, &1_700_000_000u64.to_le_bytes());
+    write(&mut data, IS_INITIALIZED, &[1]);
+    write(&mut data, UPDATE_AUTHORITY, update_authority.as_ref());
+    write(&mut data, CHANGE_DELAY, &3_600u64.to_le_bytes());
+    write(&mut data, IS_PAUSED, &[1]);
+    write(&mut data, MAX_RTP_BPS, &9_500u32.to_le_bytes());
+
+    // High bank: tier, reward per hour, Gorilla weight, then its second outcome
+    let high = BANKS + 2 * BANK_LEN;
+    write(&mut data, high, &[BankTierRisk::High as u8]);
+    write(&mut data, high + 1, &250u64.to_le_bytes());
+    write(&mut data, high + 25, &10_000u64.to_le_bytes());
+    write(&mut data, high + 66, &[2]);
+    let outcome = high + BANK_HEADER_LEN + OUTCOME_LEN;
+    write(&mut data, outcome, &40_000u32.to_le_bytes());
+    write(
+        &mut data,
+        outcome + 5,
+        &[1, NegativeOutcome::Arrested as u8],
+    );
+
+    // Gorilla role: type, boost, odds shift
+    let gorilla = ROLES + ROLE_LEN;
+    write(&mut data, gorilla, &[RoleType::Gorrila as u8]);
+    write(&mut data, gorilla + 1, &1_500u16.to_le_bytes());
+    write(&mut data, gorilla + 3, &2_000u32.to_le_bytes());
+
+    let global = decode::global(&data).unwrap();
+    assert_eq!({ global.collection }, collection);
+    assert_eq!({ global.end_date }, 1_700_000_000);
+    assert!(global.is_initialized());
+    assert_eq!({ global.update_authority }, update_authority);
+    assert_eq!({ global.change_delay }, 3_600);
+    assert!(global.is_paused());
+    assert_eq!({ global.max_rtp_bps }, 9_500);
+
+    let bank = global.bank(BankTierRisk::High);
+    assert!(matches!(bank.bank_tier(), BankTierRisk::High));
+    assert_eq!({ bank.reward_per_hour }, 250);
+    assert_eq!(bank.total_weight(), 10_000);
+    assert_eq!(bank.outcomes().len(), 2);
+    assert_eq!({ bank.outcomes()[1].odds }, 40_000);
+    assert!(bank.outcomes()[1].is_negative());
+    assert!(matches!(
+        bank.outcomes()[1].negative_outcome(),
+        NegativeOutcome::Arrested
+    ));
+
+    let role = global.role(RoleType::Gorrila);
+    assert!(matches!(role.role_type(), RoleType::Gorrila));
+    assert_eq!({ role.reward_boost_bps }, 1_500);
+    assert_eq!({ role.odds_shift }, 2_000);
+}
+
+#[test]
+fn global_rejects_bytes_the_program_never_writes() {
+    let mut data = blank_global();
+    assert!(decode::global(&data).is_ok());
+
+    data[IS_PAUSED] = 2;
+    assert!(decode::global(&data).is_err());
+    data[IS_PAUSED] = 0;
+
+    data[BANKS + BANK_LEN] = 3;
+    assert!(decode::global(&data).is_err());
+    data[BANKS + BANK_LEN] = 0;
+
+    // Unused outcome slots are checked too
+    data[BANKS + BANK_HEADER_LEN + (MAX_BANK_OUTCOMES - 1) * OUTCOME_LEN + 6] = 5;
+    assert!(decode::global(&data).is_err());
+    data[BANKS + BANK_HEADER_LEN + (MAX_BANK_OUTCOMES - 1) * OUTCOME_LEN + 6] = 0;
+
+    data[ROLES] = 2;
+    assert!(decode::global(&data).is_err());
+}
@@ -0,0 +1,37 @@
+use anchor_lang::{prelude::Pubkey, AccountSerialize};
+use heist::state::{BankTierRisk, PlayerStakeInfo, RoleType};
+use heist_client::{decode, filters::*};
+
+#[test]
+fn stake_offsets_match_serialized_layout() {
+    let owner = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let stake = PlayerStakeInfo {
+        owner,
+        mint,
+        bank: BankTierRisk::High,
+        stake_start_time: 42,
+        role: RoleType::Gorrila,
+        is_compressed: true,
+        rarity: 12_500,
+    };
+    let mut data = Vec::new();
+    stake.try_serialize(&mut data).unwrap();
+
+    assert_eq!(data.len(), PlayerStakeInfo::len());
+    assert_eq!(
+        &data[STAKE_OWNER_OFFSET..STAKE_OWNER_OFFSET + 32],
+        owner.as_ref()
+    );
+    assert_eq!(
+        &data[STAKE_MINT_OFFSET..STAKE_MINT_OFFSET + 32],
+        mint.as_ref()
+    );
+    assert_eq!(data[STAKE_BANK_OFFSET], BankTierRisk::High as u8);
+    assert_eq!(data[STAKE_ROLE_OFFSET], RoleType::Gorrila as u8);
+    assert_eq!(data[STAKE_IS_COMPRESSED_OFFSET], 1);
+
+    let decoded = decode::player_stake_info(&data).unwrap();
+    assert_eq!(decoded.owner, owner);
+    assert_eq!(decoded.rarity, 12_500);
+}
@@ -0,0 +1,130 @@
+use heist::state::{
+    Bank, BankOutcome, BankOutcomeArgs, BankTierRisk, ConfigChange, NegativeOutcome,
+    Role as OnChainRole, RoleType,
+};
+
+use crate::{BankConfig, Config, Negative, OutcomeConfig, Role, RoleConfig, Tier};
+
+impl From<Tier> for BankTierRisk {
+    fn from(tier: Tier) -> BankTierRisk {
+        match tier {
+            Tier::Low => BankTierRisk::Low,
+            Tier::Mid => BankTierRisk::Mid,
+            Tier::High => BankTierRisk::High,
+        }
+    }
+}
+
+impl From<Role> for RoleType {
+    fn from(role: Role) -> RoleType {
+        match role {
+            Role::Chimp => RoleType::Chimp,
+            Role::Gorrila => RoleType::Gorrila,
+        }
+    }
+}
+
+impl From<Negative> for NegativeOutcome {
+    fn from(negative: Negative) -> NegativeOutcome {
+        match negative {
+            Negative::None => NegativeOutcome::None,
+            Negative::Fumbled => NegativeOutcome::Fumbled,
+            Negative::Confiscation => NegativeOutcome::Confiscation,
+            Negative::Arrested => NegativeOutcome::Arrested,
+            Negative::Rekt => NegativeOutcome::Rekt,
+        }
+    }
+}
+
+impl From<&OutcomeConfig> for BankOutcomeArgs {
+    fn from(outcome: &OutcomeConfig) -> BankOutcomeArgs {
+        BankOutcomeArgs {
+            odds: outcome.odds,
+            payout_multiplier: outcome.payout_multiplier,
+            is_negative: outcome.negative_outcome != Negative::None,
+            negative_outcome: outcome.negative_outcome.into(),
+        }
+    }
+}
+
+impl From<&RoleConfig> for OnChainRole {
+    fn from(role: &RoleConfig) -> OnChainRole {
+        OnChainRole {
+            role_type: RoleType::from(role.role) as u8,
+            reward_boost_bps: role.reward_boost_bps,
+            odds_shift: role.odds_shift,
+            jail_time_reduction: role.jail_time_reduction,
+        }
+    }
+}
+
+impl BankConfig {
+    pub fn reward_per_hour(&self, config: &Config) -> u64 {
+        self.reward_per_hour.unwrap_or(config.pool.reward_per_hour)
+    }
+
+    pub fn outcome_args(&self) -> Vec<BankOutcomeArgs> {
+        self.outcomes.iter().map(BankOutcomeArgs::from).collect()
+    }
+
+    pub fn allowed_role_types(&self) -> Option<Vec<RoleType>> {
+        self.allowed_roles
+            .as_ref()
+            .map(|roles| roles.iter().map(|&role| role.into()).collect())
+    }
+
+    // The bank as it sits in Global once every change is applied
+    pub fn to_bank(&self, config: &Config) -> Bank {
+        let outcomes = self
+            .outcome_args()
+            .into_iter()
+            .map(BankOutcome::from)
+            .collect::<Vec<_>>();
+        let mut bank = Bank::new(self.tier.into(), self.reward_per_hour(config), &outcomes);
+        if let Some(roles) = self.allowed_role_types() {
+            bank.set_allowed_roles(&roles);
+        }
+        bank
+    }
+}
+
+impl Config {
+    // Timelocked changes that bring a live pool in line with this file, the
+    // schedule's end_date is set by init instead
+    pub fn to_changes(&self) -> Vec<ConfigChange> {
+        let mut changes = Vec::new();
+        // Tables are checked against the bounds when they execute, so these go
+        // first and the changes have to be executed in id order
+        if self.pool.min_rtp_bps.is_some() || self.pool.max_rtp_bps.is_some() {
+            changes.push(ConfigChange::UpdateRtpBounds {
+                min_rtp_bps: self.pool.min_rtp_bps.unwrap_or(0),
+                max_rtp_bps: self.pool.max_rtp_bps.unwrap_or(u32::MAX),
+            });
+        }
+        for bank in &self.banks {
+            changes.push(ConfigChange::UpdateBank {
+                bank_tier_risk: bank.tier.into(),
+                reward_per_hour: bank.reward_per_hour(self),
+                bank_outcomes: bank.outcome_args(),
+            });
+            if let Some(allowed_roles) = bank.allowed_role_types() {
+                changes.push(ConfigChange::UpdateBankRoles {
+                    bank_tier_risk: bank.tier.into(),
+                    allowed_roles,
+                });
+            }
+        }
+        for role in &self.roles {
+            changes.push(ConfigChange::UpdateRole {
+                role_type: role.role.into(),
+                reward_boost_bps: role.reward_boost_bps,
+                odds_shift: role.odds_shift,
+                jail_time_reduction: role.jail_time_reduction,
+            });
+        }
+        if let Some(change_delay) = self.schedule.change_delay {
+            changes.push(ConfigChange::UpdateChangeDelay { change_delay });
+        }
+        changes
+    }
+}
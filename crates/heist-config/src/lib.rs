@@ -0,0 +1,155 @@
+mod convert;
+mod validate;
+
+pub use validate::ValidationError;
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, io, path::Path};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("{}", join_lines(.0))]
+    Invalid(Vec<ValidationError>),
+}
+
+// See pool.example.toml for the layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub pool: PoolConfig,
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    #[serde(default)]
+    pub banks: Vec<BankConfig>,
+    #[serde(default)]
+    pub roles: Vec<RoleConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolConfig {
+    // Rate for banks that don't set their own
+    pub reward_per_hour: u64,
+    // Advertised return-to-player range, every bank table has to fall in it
+    pub min_rtp_bps: Option<u32>,
+    pub max_rtp_bps: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleConfig {
+    // Unix timestamp rewards are funded until
+    pub end_date: Option<u64>,
+    // Seconds queued config changes wait before executing
+    pub change_delay: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BankConfig {
+    pub tier: Tier,
+    pub reward_per_hour: Option<u64>,
+    // Every role when omitted
+    pub allowed_roles: Option<Vec<Role>>,
+    // Checked in order against the roll
+    pub outcomes: Vec<OutcomeConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutcomeConfig {
+    // Out of TOTAL_ODDS
+    pub odds: u32,
+    pub payout_multiplier: u8,
+    #[serde(default)]
+    pub negative_outcome: Negative,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoleConfig {
+    pub role: Role,
+    #[serde(default)]
+    pub reward_boost_bps: u16,
+    #[serde(default)]
+    pub odds_shift: u32,
+    #[serde(default)]
+    pub jail_time_reduction: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tier {
+    Low,
+    Mid,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Role {
+    Chimp,
+    Gorrila,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Negative {
+    #[default]
+    None,
+    Fumbled,
+    Confiscation,
+    Arrested,
+    Rekt,
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl Config {
+    pub fn from_toml_str(contents: &str) -> Result<Config, ConfigError> {
+        Config::checked(toml::from_str(contents)?)
+    }
+
+    pub fn from_json_str(contents: &str) -> Result<Config, ConfigError> {
+        Config::checked(serde_json::from_str(contents)?)
+    }
+
+    // JSON for .json files, TOML for anything else
+    pub fn load(path: &Path) -> Result<Config, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Config::from_json_str(&contents),
+            _ => Config::from_toml_str(&contents),
+        }
+    }
+
+    fn checked(config: Config) -> Result<Config, ConfigError> {
+        let errors = config.validate();
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError::Invalid(errors))
+        }
+    }
+}
+
+fn join_lines(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(|error| error.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,143 @@
+use heist::economics::TableStats;
+use heist::state::{ARREST_JAIL_TIME, MAX_BANK_OUTCOMES, TOTAL_ODDS};
+
+use crate::{Config, Negative, Role, Tier};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    #[error("odds sum to {} in bank {bank}, expected {}", group(.total), group(&TOTAL_ODDS))]
+    OddsSum { bank: Tier, total: u64 },
+    #[error(
+        "bank {bank} has {count} outcomes, at most {} fit on chain",
+        MAX_BANK_OUTCOMES
+    )]
+    TooManyOutcomes { bank: Tier, count: usize },
+    #[error(
+        "RTP {} in bank {bank} is outside {} - {}",
+        percent(.rtp_bps),
+        percent(.min_rtp_bps),
+        percent(.max_rtp_bps)
+    )]
+    RtpOutOfBounds {
+        bank: Tier,
+        rtp_bps: u64,
+        min_rtp_bps: u64,
+        max_rtp_bps: u64,
+    },
+    #[error("minimum RTP {} is above the maximum {}", percent(.min_rtp_bps), percent(.max_rtp_bps))]
+    RtpBounds { min_rtp_bps: u64, max_rtp_bps: u64 },
+    #[error("bank {bank} lists a positive outcome after a negative one")]
+    OutcomeOrder { bank: Tier },
+    #[error("bank {bank} is configured more than once")]
+    DuplicateBank { bank: Tier },
+    #[error("bank {bank} allows no roles")]
+    NoAllowedRoles { bank: Tier },
+    #[error("role {role} is configured more than once")]
+    DuplicateRole { role: Role },
+    #[error("odds shift {} for role {role} exceeds {}", group(.odds_shift), group(&TOTAL_ODDS))]
+    OddsShift { role: Role, odds_shift: u32 },
+    #[error(
+        "jail time reduction {jail_time_reduction}s for role {role} exceeds the {}s arrest",
+        ARREST_JAIL_TIME
+    )]
+    JailTimeReduction {
+        role: Role,
+        jail_time_reduction: u64,
+    },
+}
+
+impl Config {
+    // Same rules the program enforces on queued changes, all errors at once
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let min_rtp_bps = u64::from(self.pool.min_rtp_bps.unwrap_or(0));
+        let max_rtp_bps = u64::from(self.pool.max_rtp_bps.unwrap_or(u32::MAX));
+        if min_rtp_bps > max_rtp_bps {
+            errors.push(ValidationError::RtpBounds {
+                min_rtp_bps,
+                max_rtp_bps,
+            });
+        }
+
+        for (index, bank) in self.banks.iter().enumerate() {
+            if self.banks[..index]
+                .iter()
+                .any(|other| other.tier == bank.tier)
+            {
+                errors.push(ValidationError::DuplicateBank { bank: bank.tier });
+            }
+            if bank.outcomes.len() > MAX_BANK_OUTCOMES {
+                errors.push(ValidationError::TooManyOutcomes {
+                    bank: bank.tier,
+                    count: bank.outcomes.len(),
+                });
+            }
+            if bank.outcomes.windows(2).any(|pair| {
+                pair[0].negative_outcome != Negative::None
+                    && pair[1].negative_outcome == Negative::None
+            }) {
+                errors.push(ValidationError::OutcomeOrder { bank: bank.tier });
+            }
+            let total: u64 = bank.outcomes.iter().map(|o| u64::from(o.odds)).sum();
+            if total != u64::from(TOTAL_ODDS) {
+                errors.push(ValidationError::OddsSum {
+                    bank: bank.tier,
+                    total,
+                });
+            }
+            let rtp_bps = TableStats::from_args(&bank.outcome_args()).rtp_bps();
+            if rtp_bps < min_rtp_bps || rtp_bps > max_rtp_bps {
+                errors.push(ValidationError::RtpOutOfBounds {
+                    bank: bank.tier,
+                    rtp_bps,
+                    min_rtp_bps,
+                    max_rtp_bps,
+                });
+            }
+            if matches!(&bank.allowed_roles, Some(roles) if roles.is_empty()) {
+                errors.push(ValidationError::NoAllowedRoles { bank: bank.tier });
+            }
+        }
+
+        for (index, role) in self.roles.iter().enumerate() {
+            if self.roles[..index]
+                .iter()
+                .any(|other| other.role == role.role)
+            {
+                errors.push(ValidationError::DuplicateRole { role: role.role });
+            }
+            if role.odds_shift > TOTAL_ODDS {
+                errors.push(ValidationError::OddsShift {
+                    role: role.role,
+                    odds_shift: role.odds_shift,
+                });
+            }
+            if role.jail_time_reduction > ARREST_JAIL_TIME {
+                errors.push(ValidationError::JailTimeReduction {
+                    role: role.role,
+                    jail_time_reduction: role.jail_time_reduction,
+                });
+            }
+        }
+
+        errors
+    }
+}
+
+// 9_850 -> 98.50%
+fn percent(bps: &u64) -> String {
+    format!("{}.{:02}%", bps / 100, bps % 100)
+}
+
+// 99998 -> 99_998, the way odds are written in the config
+fn group<T: Copy + Into<u64>>(value: &T) -> String {
+    let digits = (*value).into().to_string();
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push('_');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
@@ -0,0 +1,110 @@
+use heist_config::{Config, ConfigError, Tier, ValidationError};
+use std::path::Path;
+
+const SHORT_HIGH_BANK: &str = r#"
+[pool]
+reward_per_hour = 100
+
+[[banks]]
+tier = "High"
+outcomes = [
+    { odds = 99_990, payout_multiplier = 1 },
+    { odds = 8, payout_multiplier = 0, negative_outcome = "Arrested" },
+]
+"#;
+
+#[test]
+fn example_config_is_valid() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("pool.example.toml");
+    let config = Config::load(&path).unwrap();
+
+    assert_eq!(config.banks.len(), 3);
+    // The RTP bounds, a bank table and a role list per bank that sets one, the role, the delay
+    assert_eq!(config.to_changes().len(), 1 + 3 + 2 + 1 + 1);
+}
+
+#[test]
+fn reports_odds_sum_per_bank() {
+    let err = Config::from_toml_str(SHORT_HIGH_BANK).unwrap_err();
+
+    match &err {
+        ConfigError::Invalid(errors) => assert_eq!(
+            errors,
+            &[ValidationError::OddsSum {
+                bank: Tier::High,
+                total: 99_998
+            }]
+        ),
+        other => panic!("unexpected error {}", other),
+    }
+    assert_eq!(
+        err.to_string(),
+        "odds sum to 99_998 in bank High, expected 100_000"
+    );
+}
+
+#[test]
+fn reports_tables_outside_rtp_bounds() {
+    let toml = r#"
+[pool]
+reward_per_hour = 100
+min_rtp_bps = 9_500
+max_rtp_bps = 10_000
+
+[[banks]]
+tier = "Mid"
+outcomes = [
+    { odds = 96_000, payout_multiplier = 1 },
+    { odds = 4_000, payout_multiplier = 2 },
+]
+"#;
+    let err = Config::from_toml_str(toml).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "RTP 104.00% in bank Mid is outside 95.00% - 100.00%"
+    );
+}
+
+#[test]
+fn reports_every_error() {
+    let json = r#"{
+        "pool": { "reward_per_hour": 100 },
+        "banks": [
+            { "tier": "Low", "allowed_roles": [], "outcomes": [{ "odds": 100000, "payout_multiplier": 1 }] }
+        ],
+        "roles": [
+            { "role": "Chimp", "odds_shift": 100001 },
+            { "role": "Chimp" }
+        ]
+    }"#;
+    let err = Config::from_json_str(json).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "bank Low allows no roles\n\
+         odds shift 100_001 for role Chimp exceeds 100_000\n\
+         role Chimp is configured more than once"
+    );
+}
+
+#[test]
+fn reports_negative_outcomes_before_positive_ones() {
+    let toml = r#"
+[pool]
+reward_per_hour = 100
+
+[[banks]]
+tier = "Low"
+outcomes = [
+    { odds = 40_000, payout_multiplier = 0, negative_outcome = "Fumbled" },
+    { odds = 60_000, payout_multiplier = 2 },
+]
+"#;
+    let err = Config::from_toml_str(toml).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "bank Low lists a positive outcome after a negative one"
+    );
+}
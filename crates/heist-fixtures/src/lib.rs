@@ -0,0 +1,7 @@
+mod nft;
+mod tree;
+mod validator;
+
+pub use nft::{NftBuilder, NftFixture};
+pub use tree::{TreeFixture, MAX_BUFFER_SIZE, MAX_DEPTH};
+pub use validator::write_account_json;
@@ -0,0 +1,227 @@
+use borsh::BorshSerialize;
+use mpl_token_metadata::state::{
+    Collection, Creator, Data, Key, MasterEditionV2, Metadata, TokenStandard,
+    MAX_MASTER_EDITION_LEN, MAX_METADATA_LEN,
+};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account, program_option::COption, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use std::{io, path::Path};
+
+use crate::validator::write_account_json;
+
+// Everything Metaplex would have left behind for a minted NFT
+pub struct NftBuilder {
+    mint: Pubkey,
+    owner: Pubkey,
+    update_authority: Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Vec<Creator>,
+    collection: Option<Collection>,
+    token_standard: Option<TokenStandard>,
+}
+
+pub struct NftFixture {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    // Owner's associated token account holding the NFT
+    pub token_account: Pubkey,
+    pub metadata: Pubkey,
+    pub edition: Pubkey,
+    pub accounts: Vec<(Pubkey, Account)>,
+}
+
+impl NftBuilder {
+    pub fn new(owner: Pubkey) -> NftBuilder {
+        NftBuilder {
+            mint: Pubkey::new_unique(),
+            owner,
+            update_authority: owner,
+            name: "Heist".to_string(),
+            symbol: "HEIST".to_string(),
+            uri: "https://example.com/heist.json".to_string(),
+            seller_fee_basis_points: 0,
+            creators: Vec::new(),
+            collection: None,
+            token_standard: Some(TokenStandard::NonFungible),
+        }
+    }
+
+    pub fn mint(mut self, mint: Pubkey) -> NftBuilder {
+        self.mint = mint;
+        self
+    }
+
+    pub fn update_authority(mut self, update_authority: Pubkey) -> NftBuilder {
+        self.update_authority = update_authority;
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> NftBuilder {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn symbol(mut self, symbol: &str) -> NftBuilder {
+        self.symbol = symbol.to_string();
+        self
+    }
+
+    pub fn uri(mut self, uri: &str) -> NftBuilder {
+        self.uri = uri.to_string();
+        self
+    }
+
+    pub fn seller_fee_basis_points(mut self, seller_fee_basis_points: u16) -> NftBuilder {
+        self.seller_fee_basis_points = seller_fee_basis_points;
+        self
+    }
+
+    pub fn creator(mut self, address: Pubkey, verified: bool, share: u8) -> NftBuilder {
+        self.creators.push(Creator {
+            address,
+            verified,
+            share,
+        });
+        self
+    }
+
+    pub fn collection(mut self, key: Pubkey, verified: bool) -> NftBuilder {
+        self.collection = Some(Collection { key, verified });
+        self
+    }
+
+    pub fn token_standard(mut self, token_standard: Option<TokenStandard>) -> NftBuilder {
+        self.token_standard = token_standard;
+        self
+    }
+
+    pub fn build(self) -> NftFixture {
+        let (metadata, _) = mpl_token_metadata::pda::find_metadata_account(&self.mint);
+        let (edition, _) = mpl_token_metadata::pda::find_master_edition_account(&self.mint);
+        let token_account = get_associated_token_address(&self.owner, &self.mint);
+
+        // Minting hands both authorities to the master edition
+        let mint = Mint {
+            mint_authority: COption::Some(edition),
+            supply: 1,
+            decimals: 0,
+            is_initialized: true,
+            freeze_authority: COption::Some(edition),
+        };
+        let token = TokenAccount {
+            mint: self.mint,
+            owner: self.owner,
+            amount: 1,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        let master_edition = MasterEditionV2 {
+            key: Key::MasterEditionV2,
+            supply: 0,
+            max_supply: Some(0),
+        };
+        let metadata_account = Metadata {
+            key: Key::MetadataV1,
+            update_authority: self.update_authority,
+            mint: self.mint,
+            data: Data {
+                name: self.name,
+                symbol: self.symbol,
+                uri: self.uri,
+                seller_fee_basis_points: self.seller_fee_basis_points,
+                creators: if self.creators.is_empty() {
+                    None
+                } else {
+                    Some(self.creators)
+                },
+            },
+            primary_sale_happened: false,
+            is_mutable: true,
+            edition_nonce: None,
+            token_standard: self.token_standard,
+            collection: self.collection,
+            uses: None,
+            collection_details: None,
+        };
+
+        NftFixture {
+            mint: self.mint,
+            owner: self.owner,
+            token_account,
+            metadata,
+            edition,
+            accounts: vec![
+                (self.mint, packed_account(mint, &spl_token::ID)),
+                (token_account, packed_account(token, &spl_token::ID)),
+                (
+                    metadata,
+                    borsh_account(&metadata_account, MAX_METADATA_LEN, &mpl_token_metadata::ID),
+                ),
+                (
+                    edition,
+                    borsh_account(
+                        &master_edition,
+                        MAX_MASTER_EDITION_LEN,
+                        &mpl_token_metadata::ID,
+                    ),
+                ),
+            ],
+        }
+    }
+}
+
+impl NftFixture {
+    pub fn add_to(&self, program_test: &mut ProgramTest) {
+        for (address, account) in &self.accounts {
+            program_test.add_account(*address, account.clone());
+        }
+    }
+
+    // Same accounts written into an already started test
+    pub fn set_in(&self, context: &mut ProgramTestContext) {
+        for (address, account) in &self.accounts {
+            context.set_account(address, &account.clone().into());
+        }
+    }
+
+    // One file per account, pass each to solana-test-validator --account <address> <file>
+    pub fn write_validator_accounts(&self, dir: &Path) -> io::Result<()> {
+        for (address, account) in &self.accounts {
+            write_account_json(&dir.join(format!("{}.json", address)), address, account)?;
+        }
+        Ok(())
+    }
+}
+
+fn packed_account<T: Pack>(state: T, owner: &Pubkey) -> Account {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    rent_exempt(data, owner)
+}
+
+// Metaplex allocates the max length up front and leaves the tail zeroed
+fn borsh_account<T: BorshSerialize>(state: &T, len: usize, owner: &Pubkey) -> Account {
+    let mut data = state.try_to_vec().unwrap();
+    data.resize(len, 0);
+    rent_exempt(data, owner)
+}
+
+pub(crate) fn rent_exempt(data: Vec<u8>, owner: &Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,115 @@
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{account::Account, hash::hashv, keccak, pubkey::Pubkey};
+use spl_concurrent_merkle_tree::concurrent_merkle_tree::ConcurrentMerkleTree;
+
+use crate::nft::rent_exempt;
+
+// Smallest tree Bubblegum accepts, 32 leaves and no canopy so proofs carry every level
+pub const MAX_DEPTH: usize = 5;
+pub const MAX_BUFFER_SIZE: usize = 8;
+const HEADER_LEN: usize = 56;
+
+// A Bubblegum tree as create_tree and mint_v1 would have left it. Leaves are
+// the caller's LeafSchema nodes, kept here so proofs follow later changes
+pub struct TreeFixture {
+    pub merkle_tree: Pubkey,
+    pub tree_authority: Pubkey,
+    pub leaves: Vec<[u8; 32]>,
+    pub accounts: Vec<(Pubkey, Account)>,
+}
+
+impl TreeFixture {
+    pub fn new(merkle_tree: Pubkey, tree_creator: Pubkey, leaves: Vec<[u8; 32]>) -> TreeFixture {
+        assert!(leaves.len() <= 1 << MAX_DEPTH, "tree holds 32 leaves");
+        let (tree_authority, _) =
+            Pubkey::find_program_address(&[merkle_tree.as_ref()], &mpl_bubblegum::ID);
+
+        let mut tree = ConcurrentMerkleTree::<MAX_DEPTH, MAX_BUFFER_SIZE>::new();
+        tree.initialize().unwrap();
+        for leaf in &leaves {
+            tree.append(*leaf).unwrap();
+        }
+        // CompressionAccountType::ConcurrentMerkleTree, header V1, then its fields
+        let mut tree_data = Vec::with_capacity(HEADER_LEN);
+        tree_data.extend_from_slice(&[1, 0]);
+        tree_data.extend_from_slice(&(MAX_BUFFER_SIZE as u32).to_le_bytes());
+        tree_data.extend_from_slice(&(MAX_DEPTH as u32).to_le_bytes());
+        tree_data.extend_from_slice(tree_authority.as_ref());
+        tree_data.extend_from_slice(&0u64.to_le_bytes());
+        tree_data.resize(HEADER_LEN, 0);
+        tree_data.extend_from_slice(bytemuck::bytes_of(&tree));
+
+        // TreeConfig, zeroed room after it for fields later Bubblegum versions add
+        let mut config_data = hashv(&[b"account:TreeConfig"]).to_bytes()[..8].to_vec();
+        config_data.extend_from_slice(tree_creator.as_ref());
+        config_data.extend_from_slice(tree_creator.as_ref());
+        config_data.extend_from_slice(&(1u64 << MAX_DEPTH).to_le_bytes());
+        config_data.extend_from_slice(&(leaves.len() as u64).to_le_bytes());
+        config_data.resize(config_data.len() + 8, 0);
+
+        TreeFixture {
+            merkle_tree,
+            tree_authority,
+            leaves,
+            accounts: vec![
+                (
+                    merkle_tree,
+                    rent_exempt(tree_data, &spl_account_compression::ID),
+                ),
+                (tree_authority, rent_exempt(config_data, &mpl_bubblegum::ID)),
+            ],
+        }
+    }
+
+    pub fn add_to(&self, program_test: &mut ProgramTest) {
+        for (address, account) in &self.accounts {
+            program_test.add_account(*address, account.clone());
+        }
+    }
+
+    pub fn set_in(&self, context: &mut ProgramTestContext) {
+        for (address, account) in &self.accounts {
+            context.set_account(address, &account.clone().into());
+        }
+    }
+
+    // Mirrors a leaf Bubblegum rewrote on chain
+    pub fn set_leaf(&mut self, index: u32, leaf: [u8; 32]) {
+        self.leaves[index as usize] = leaf;
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels().last().unwrap()[0]
+    }
+
+    // Sibling at every level from the leaf up, passed as remaining accounts
+    pub fn proof(&self, index: u32) -> Vec<Pubkey> {
+        let mut position = index as usize;
+        self.levels()[..MAX_DEPTH]
+            .iter()
+            .map(|level| {
+                let sibling = Pubkey::new_from_array(level[position ^ 1]);
+                position /= 2;
+                sibling
+            })
+            .collect()
+    }
+
+    // Full levels from the leaves to the root, unset leaves are zero.
+    // Children are hashed left then right, unlike the role tree
+    fn levels(&self) -> Vec<Vec<[u8; 32]>> {
+        let mut level = self.leaves.clone();
+        level.resize(1 << MAX_DEPTH, [0; 32]);
+        let mut levels = vec![level];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| keccak::hashv(&[&pair[0], &pair[1]]).0)
+                .collect();
+            levels.push(next);
+        }
+        levels
+    }
+}
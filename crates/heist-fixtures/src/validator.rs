@@ -0,0 +1,18 @@
+use serde_json::json;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::{fs, io, path::Path};
+
+// Same layout `solana account --output json` writes
+pub fn write_account_json(path: &Path, address: &Pubkey, account: &Account) -> io::Result<()> {
+    let contents = json!({
+        "pubkey": address.to_string(),
+        "account": {
+            "lamports": account.lamports,
+            "data": [base64::encode(&account.data), "base64"],
+            "owner": account.owner.to_string(),
+            "executable": account.executable,
+            "rentEpoch": account.rent_epoch,
+        },
+    });
+    fs::write(path, serde_json::to_string_pretty(&contents)?)
+}
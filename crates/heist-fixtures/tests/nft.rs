@@ -0,0 +1,43 @@
+use heist_fixtures::NftBuilder;
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+
+#[test]
+fn metadata_deserializes_with_verified_collection() {
+    let owner = Pubkey::new_unique();
+    let collection = NftBuilder::new(owner).build();
+    let nft = NftBuilder::new(owner)
+        .creator(owner, true, 100)
+        .collection(collection.mint, true)
+        .build();
+
+    let (_, metadata_account) = nft
+        .accounts
+        .iter()
+        .find(|(address, _)| *address == nft.metadata)
+        .unwrap();
+    let metadata = Metadata::safe_deserialize(&metadata_account.data).unwrap();
+    let metadata_collection = metadata.collection.unwrap();
+
+    assert_eq!(metadata.mint, nft.mint);
+    assert_eq!(metadata_collection.key, collection.mint);
+    assert!(metadata_collection.verified);
+    assert_eq!(metadata.data.creators.unwrap()[0].address, owner);
+}
+
+#[test]
+fn owner_holds_the_only_token() {
+    let owner = Pubkey::new_unique();
+    let nft = NftBuilder::new(owner).build();
+
+    let (_, token_account) = nft
+        .accounts
+        .iter()
+        .find(|(address, _)| *address == nft.token_account)
+        .unwrap();
+    let token = spl_token::state::Account::unpack(&token_account.data).unwrap();
+
+    assert_eq!(token.owner, owner);
+    assert_eq!(token.mint, nft.mint);
+    assert_eq!(token.amount, 1);
+}
@@ -0,0 +1,50 @@
+use heist_fixtures::{TreeFixture, MAX_BUFFER_SIZE, MAX_DEPTH};
+use solana_sdk::pubkey::Pubkey;
+use spl_concurrent_merkle_tree::concurrent_merkle_tree::ConcurrentMerkleTree;
+
+type Tree = ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>;
+
+fn on_chain_tree(fixture: &TreeFixture) -> Tree {
+    let (_, account) = fixture
+        .accounts
+        .iter()
+        .find(|(address, _)| *address == fixture.merkle_tree)
+        .unwrap();
+    bytemuck::pod_read_unaligned(&account.data[56..56 + std::mem::size_of::<Tree>()])
+}
+
+#[test]
+fn proofs_match_the_serialized_tree() {
+    let leaves: Vec<[u8; 32]> = (1..=5).map(|seed| [seed; 32]).collect();
+    let fixture = TreeFixture::new(Pubkey::new_unique(), Pubkey::new_unique(), leaves.clone());
+    let tree = on_chain_tree(&fixture);
+
+    assert_eq!(tree.get_root(), fixture.root());
+    for (index, leaf) in leaves.iter().enumerate() {
+        let proof: Vec<[u8; 32]> = fixture
+            .proof(index as u32)
+            .iter()
+            .map(|node| node.to_bytes())
+            .collect();
+        assert!(tree
+            .prove_leaf(fixture.root(), *leaf, &proof, index as u32)
+            .is_ok());
+    }
+}
+
+#[test]
+fn changed_leaf_moves_the_root() {
+    let mut fixture = TreeFixture::new(Pubkey::new_unique(), Pubkey::new_unique(), vec![[1; 32]]);
+    let mut tree = on_chain_tree(&fixture);
+    let proof: Vec<[u8; 32]> = fixture
+        .proof(0)
+        .iter()
+        .map(|node| node.to_bytes())
+        .collect();
+
+    tree.set_leaf(fixture.root(), [1; 32], [2; 32], &proof, 0)
+        .unwrap();
+    fixture.set_leaf(0, [2; 32]);
+
+    assert_eq!(tree.get_root(), fixture.root());
+}
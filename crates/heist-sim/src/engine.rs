@@ -0,0 +1,207 @@
+use heist::economics::{accrued_reward, committed_reward, payout, select_outcome, shifted_roll};
+use heist::state::{
+    BankTierRisk, NegativeOutcome, ARREST_JAIL_TIME, MAX_BANKS, MAX_ROLES, TOTAL_ODDS,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    report::{Report, TierReport},
+    SimConfig, Table,
+};
+
+#[derive(Default, Clone, Copy)]
+struct TierStats {
+    settlements: u64,
+    negatives: u64,
+    payout_sum: f64,
+    payout_square_sum: f64,
+    base_sum: f64,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Player {
+    // Next hour the player looks for a bank
+    free_at: u64,
+    stake_count: u64,
+    // Bank the live stake sits in and the hour it started
+    stake: Option<(usize, u64)>,
+}
+
+// Role weights per bank, what Global tracks to price live stakes
+#[derive(Default)]
+struct Commitments {
+    weights: [[u64; MAX_ROLES]; MAX_BANKS],
+    weighted_starts: [[u128; MAX_ROLES]; MAX_BANKS],
+}
+
+impl Commitments {
+    fn add(&mut self, bank: usize, role: usize, rarity: u16, start: u64) {
+        self.weights[bank][role] += u64::from(rarity);
+        self.weighted_starts[bank][role] += u128::from(rarity) * u128::from(start);
+    }
+
+    fn remove(&mut self, bank: usize, role: usize, rarity: u16, start: u64) {
+        self.weights[bank][role] -= u64::from(rarity);
+        self.weighted_starts[bank][role] -= u128::from(rarity) * u128::from(start);
+    }
+
+    // Same sum as Global::committed_rewards
+    fn total(&self, config: &SimConfig) -> u64 {
+        let end_date = config.season_hours * 3600;
+        let mut committed = 0u64;
+        for (bank, table) in config.tables.iter().enumerate() {
+            for (role, modifiers) in config.roles.iter().enumerate() {
+                committed = committed.saturating_add(committed_reward(
+                    table.reward_per_hour,
+                    modifiers.reward_boost_bps,
+                    max_multiplier(table),
+                    self.weights[bank][role],
+                    self.weighted_starts[bank][role],
+                    end_date,
+                ));
+            }
+        }
+        committed
+    }
+}
+
+pub fn simulate(config: &SimConfig) -> Report {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut stats = [TierStats::default(); MAX_BANKS];
+    let days = ((config.season_hours + 23) / 24).max(1) as usize;
+    let mut drain = vec![0f64; days];
+    let mut insolvent_runs = 0;
+    let (mut admitted, mut rejected) = (0u64, 0u64);
+
+    for _ in 0..config.runs {
+        let mut daily_payout = vec![0u64; days];
+        let mut players = vec![Player::default(); config.players];
+        let mut commitments = Commitments::default();
+        let mut vault = config.vault_balance;
+
+        for hour in 0..=config.season_hours {
+            for (index, player) in players.iter_mut().enumerate() {
+                let strategy = config.strategies[index % config.strategies.len()];
+                let role_type = config.player_roles[index % config.player_roles.len()] as usize;
+                let role = &config.roles[role_type];
+                let hold_hours = strategy.hold_hours().max(1);
+
+                if let Some((bank, start)) = player.stake {
+                    if hour < start + hold_hours {
+                        continue;
+                    }
+                    player.stake = None;
+                    player.free_at = hour;
+                    commitments.remove(bank, role_type, config.rarity, start * 3600);
+
+                    let table = &config.tables[bank];
+                    let base = accrued_reward(
+                        table.reward_per_hour,
+                        role.reward_boost_bps,
+                        config.rarity,
+                        hold_hours * 3600,
+                    );
+                    let roll =
+                        shifted_roll(rng.gen_range(0..u64::from(TOTAL_ODDS)), role.odds_shift);
+                    let outcome = select_outcome(table.outcomes.iter().map(|o| o.odds), roll)
+                        .map(|index| &table.outcomes[index]);
+                    let payout = outcome.map_or(0, |o| payout(base, o.payout_multiplier));
+                    vault = vault.saturating_sub(payout);
+
+                    let tier_stats = &mut stats[bank];
+                    tier_stats.settlements += 1;
+                    tier_stats.payout_sum += payout as f64;
+                    tier_stats.payout_square_sum += (payout as f64).powi(2);
+                    tier_stats.base_sum += base as f64;
+                    daily_payout[((hour - 1) / 24) as usize] += payout;
+
+                    if let Some(outcome) = outcome {
+                        if outcome.is_negative() {
+                            tier_stats.negatives += 1;
+                        }
+                        // Stakes start on the hour, a started hour of jail is lost
+                        if let NegativeOutcome::Arrested = outcome.negative_outcome() {
+                            let jail_time =
+                                ARREST_JAIL_TIME.saturating_sub(role.jail_time_reduction);
+                            player.free_at += (jail_time + 3599) / 3600;
+                        }
+                    }
+                }
+
+                if hour < player.free_at || hour + hold_hours > config.season_hours {
+                    continue;
+                }
+                let bank = strategy.pick(player.stake_count, &mut rng) as usize;
+                // The program rejects the stake, the player sits the hold out
+                if !config.tables[bank].allowed_roles[role_type] {
+                    player.stake_count += 1;
+                    player.free_at = hour + hold_hours;
+                    continue;
+                }
+                // require_solvent turns the stake away, the player tries again next hour
+                commitments.add(bank, role_type, config.rarity, hour * 3600);
+                if commitments.total(config) > vault {
+                    commitments.remove(bank, role_type, config.rarity, hour * 3600);
+                    rejected += 1;
+                    player.free_at = hour + 1;
+                    continue;
+                }
+                admitted += 1;
+                player.stake_count += 1;
+                player.stake = Some((bank, hour));
+            }
+        }
+
+        let mut paid = 0;
+        let mut insolvent = false;
+        for (day, payout) in daily_payout.iter().enumerate() {
+            paid += payout;
+            drain[day] += paid as f64;
+            insolvent |= paid > config.vault_balance;
+        }
+        if insolvent {
+            insolvent_runs += 1;
+        }
+    }
+
+    let runs = config.runs.max(1) as f64;
+    Report {
+        tiers: config
+            .tables
+            .iter()
+            .zip(stats)
+            .map(|(table, stats)| tier_report(table.tier, &stats))
+            .collect(),
+        vault_balance: config.vault_balance,
+        vault_drain: drain.iter().map(|paid| (paid / runs) as u64).collect(),
+        insolvency_probability: insolvent_runs as f64 / runs,
+        rejection_rate: rejected as f64 / (admitted + rejected).max(1) as f64,
+    }
+}
+
+fn max_multiplier(table: &Table) -> u8 {
+    table
+        .outcomes
+        .iter()
+        .map(|outcome| outcome.payout_multiplier)
+        .max()
+        .unwrap_or(0)
+}
+
+// Odds are slices of 0 - 99_999 laid end to end in table order
+fn tier_report(tier: BankTierRisk, stats: &TierStats) -> TierReport {
+    let settlements = stats.settlements.max(1) as f64;
+    let mean_payout = stats.payout_sum / settlements;
+    TierReport {
+        tier,
+        settlements: stats.settlements,
+        mean_payout,
+        variance: stats.payout_square_sum / settlements - mean_payout.powi(2),
+        return_to_player: if stats.base_sum > 0.0 {
+            stats.payout_sum / stats.base_sum
+        } else {
+            0.0
+        },
+        negative_rate: stats.negatives as f64 / settlements,
+    }
+}
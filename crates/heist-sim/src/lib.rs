@@ -0,0 +1,97 @@
+mod engine;
+pub mod report;
+pub mod strategy;
+
+pub use engine::simulate;
+
+use heist::economics::season_funding;
+use heist::state::{
+    Bank, BankOutcome, BankTierRisk, Role, RoleType, BASE_RARITY_WEIGHT, MAX_BANKS, MAX_ROLES,
+};
+use heist_config::Config;
+use strategy::Strategy;
+
+// Owned copy of a bank's live table, Bank itself is packed
+#[derive(Clone)]
+pub struct Table {
+    pub tier: BankTierRisk,
+    pub reward_per_hour: u64,
+    pub outcomes: Vec<BankOutcome>,
+    // Indexed by RoleType
+    pub allowed_roles: [bool; MAX_ROLES],
+}
+
+impl From<&Bank> for Table {
+    fn from(bank: &Bank) -> Table {
+        Table {
+            tier: bank.bank_tier(),
+            reward_per_hour: bank.reward_per_hour,
+            outcomes: bank.outcomes().to_vec(),
+            allowed_roles: [bank.allows(RoleType::Chimp), bank.allows(RoleType::Gorrila)],
+        }
+    }
+}
+
+pub struct SimConfig {
+    // Indexed by BankTierRisk
+    pub tables: [Table; MAX_BANKS],
+    // Modifiers indexed by RoleType
+    pub roles: [Role; MAX_ROLES],
+    pub players: usize,
+    pub season_hours: u64,
+    // Reward tokens the vault starts the season with
+    pub vault_balance: u64,
+    pub rarity: u16,
+    // Both handed out to players round robin
+    pub player_roles: Vec<RoleType>,
+    pub strategies: Vec<Strategy>,
+    pub runs: usize,
+    pub seed: u64,
+}
+
+impl SimConfig {
+    // Program default tables, funded the way init_or_update_collection funds them
+    pub fn with_default_tables(reward_per_hour: u64, season_hours: u64) -> SimConfig {
+        SimConfig::with_banks(
+            &Bank::generate_banks(reward_per_hour),
+            Role::new(),
+            reward_per_hour,
+            season_hours,
+        )
+    }
+
+    // Banks and roles missing from the file keep the program defaults. Init
+    // only funds the pool rate, bank overrides above it draw on top ups
+    pub fn from_config(config: &Config, season_hours: u64) -> SimConfig {
+        let mut banks = Bank::generate_banks(config.pool.reward_per_hour);
+        for bank in &config.banks {
+            let bank = bank.to_bank(config);
+            banks[bank.bank_tier() as usize] = bank;
+        }
+        let mut roles = Role::new();
+        for role in &config.roles {
+            roles[RoleType::from(role.role) as usize] = role.into();
+        }
+        SimConfig::with_banks(&banks, roles, config.pool.reward_per_hour, season_hours)
+    }
+
+    fn with_banks(
+        banks: &[Bank; MAX_BANKS],
+        roles: [Role; MAX_ROLES],
+        reward_per_hour: u64,
+        season_hours: u64,
+    ) -> SimConfig {
+        SimConfig {
+            tables: [(&banks[0]).into(), (&banks[1]).into(), (&banks[2]).into()],
+            roles,
+            players: 1_000,
+            season_hours,
+            vault_balance: season_funding(reward_per_hour, season_hours * 3600),
+            rarity: BASE_RARITY_WEIGHT,
+            player_roles: vec![RoleType::Chimp],
+            strategies: vec![Strategy::Random { hold_hours: 24 }],
+            runs: 100,
+            seed: 0,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::Result;
+use clap::{ArgEnum, Parser};
+use heist::state::{BankTierRisk, RoleType};
+use heist_config::Config;
+use heist_sim::{simulate, strategy::Strategy, SimConfig};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(name = "heist-sim", about = "Simulate a season of heist bank payouts")]
+struct Cli {
+    /// Pool config to simulate, the program default tables when omitted
+    #[clap(long)]
+    config: Option<PathBuf>,
+    /// Bank rate for the default tables
+    #[clap(long, default_value = "100")]
+    reward_per_hour: u64,
+    #[clap(long, default_value = "30")]
+    season_days: u64,
+    #[clap(long, default_value = "1000")]
+    players: usize,
+    #[clap(long, default_value = "100")]
+    runs: usize,
+    /// Hours a player stays staked before unstaking
+    #[clap(long, default_value = "24")]
+    hold_hours: u64,
+    #[clap(long, arg_enum, default_value = "random")]
+    strategy: StrategyArg,
+    /// Roles handed out to players in turn, modifiers come from --config
+    #[clap(long, arg_enum, value_delimiter = ',', default_value = "chimp")]
+    roles: Vec<RoleArg>,
+    /// Overrides the vault funding init_or_update_collection would require
+    #[clap(long)]
+    vault_balance: Option<u64>,
+    #[clap(long, default_value = "0")]
+    seed: u64,
+}
+
+#[derive(Clone, Copy, ArgEnum)]
+enum StrategyArg {
+    Low,
+    Mid,
+    High,
+    Random,
+    Ladder,
+    /// One player in five on each strategy above
+    Mixed,
+}
+
+#[derive(Clone, Copy, ArgEnum)]
+enum RoleArg {
+    Chimp,
+    Gorrila,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let season_hours = cli.season_days * 24;
+    let mut config = match &cli.config {
+        Some(path) => SimConfig::from_config(&Config::load(path)?, season_hours),
+        None => SimConfig::with_default_tables(cli.reward_per_hour, season_hours),
+    };
+
+    let hold_hours = cli.hold_hours;
+    let fixed = |tier| Strategy::Fixed { tier, hold_hours };
+    config.strategies = match cli.strategy {
+        StrategyArg::Low => vec![fixed(BankTierRisk::Low)],
+        StrategyArg::Mid => vec![fixed(BankTierRisk::Mid)],
+        StrategyArg::High => vec![fixed(BankTierRisk::High)],
+        StrategyArg::Random => vec![Strategy::Random { hold_hours }],
+        StrategyArg::Ladder => vec![Strategy::Ladder { hold_hours }],
+        StrategyArg::Mixed => vec![
+            fixed(BankTierRisk::Low),
+            fixed(BankTierRisk::Mid),
+            fixed(BankTierRisk::High),
+            Strategy::Random { hold_hours },
+            Strategy::Ladder { hold_hours },
+        ],
+    };
+    config.player_roles = cli
+        .roles
+        .iter()
+        .map(|role| match role {
+            RoleArg::Chimp => RoleType::Chimp,
+            RoleArg::Gorrila => RoleType::Gorrila,
+        })
+        .collect();
+    config.players = cli.players;
+    config.runs = cli.runs;
+    config.seed = cli.seed;
+    if let Some(vault_balance) = cli.vault_balance {
+        config.vault_balance = vault_balance;
+    }
+
+    println!("{}", simulate(&config));
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use heist::state::BankTierRisk;
+use std::fmt;
+
+pub struct TierReport {
+    pub tier: BankTierRisk,
+    pub settlements: u64,
+    // Reward tokens per settlement
+    pub mean_payout: f64,
+    pub variance: f64,
+    // Paid out over accrued, 1.0 pays exactly the bank rate
+    pub return_to_player: f64,
+    pub negative_rate: f64,
+}
+
+pub struct Report {
+    pub tiers: Vec<TierReport>,
+    pub vault_balance: u64,
+    // Mean cumulative payout at the end of each day of the season
+    pub vault_drain: Vec<u64>,
+    // Share of runs where payouts outgrew the vault
+    pub insolvency_probability: f64,
+    // Stake attempts require_solvent turned away, retries included
+    pub rejection_rate: f64,
+}
+
+impl Report {
+    // First day the mean run has paid out more than the vault holds
+    pub fn mean_insolvency_day(&self) -> Option<usize> {
+        self.vault_drain
+            .iter()
+            .position(|&paid| paid > self.vault_balance)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<6} {:>12} {:>14} {:>16} {:>8} {:>10}",
+            "Bank", "Settlements", "EV", "Std dev", "RTP", "Negative"
+        )?;
+        for tier in &self.tiers {
+            writeln!(
+                f,
+                "{:<6} {:>12} {:>14.2} {:>16.2} {:>7.2}% {:>9.2}%",
+                tier_name(tier.tier),
+                tier.settlements,
+                tier.mean_payout,
+                tier.variance.sqrt(),
+                tier.return_to_player * 100.0,
+                tier.negative_rate * 100.0
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Vault balance: {}", self.vault_balance)?;
+        for (day, paid) in self.vault_drain.iter().enumerate() {
+            let left = self.vault_balance as i128 - *paid as i128;
+            writeln!(
+                f,
+                "  Day {:>3}: paid {:>16} left {:>16}",
+                day + 1,
+                paid,
+                left
+            )?;
+        }
+        writeln!(
+            f,
+            "Insolvency probability: {:.2}%",
+            self.insolvency_probability * 100.0
+        )?;
+        write!(
+            f,
+            "Stakes rejected by the vault: {:.2}%",
+            self.rejection_rate * 100.0
+        )
+    }
+}
+
+fn tier_name(tier: BankTierRisk) -> &'static str {
+    match tier {
+        BankTierRisk::Low => "Low",
+        BankTierRisk::Mid => "Mid",
+        BankTierRisk::High => "High",
+    }
+}
@@ -0,0 +1,37 @@
+use heist::state::BankTierRisk;
+use rand::Rng;
+
+// How a player picks a bank and how long they stay before unstaking
+#[derive(Clone, Copy)]
+pub enum Strategy {
+    Fixed { tier: BankTierRisk, hold_hours: u64 },
+    Random { hold_hours: u64 },
+    // Low, Mid, High, Low, ...
+    Ladder { hold_hours: u64 },
+}
+
+impl Strategy {
+    pub fn hold_hours(&self) -> u64 {
+        match *self {
+            Strategy::Fixed { hold_hours, .. }
+            | Strategy::Random { hold_hours }
+            | Strategy::Ladder { hold_hours } => hold_hours,
+        }
+    }
+
+    pub fn pick(&self, stake_count: u64, rng: &mut impl Rng) -> BankTierRisk {
+        match *self {
+            Strategy::Fixed { tier, .. } => tier,
+            Strategy::Random { .. } => tier_at(rng.gen_range(0..3)),
+            Strategy::Ladder { .. } => tier_at(stake_count % 3),
+        }
+    }
+}
+
+fn tier_at(index: u64) -> BankTierRisk {
+    match index {
+        0 => BankTierRisk::Low,
+        1 => BankTierRisk::Mid,
+        _ => BankTierRisk::High,
+    }
+}
@@ -0,0 +1,91 @@
+use heist::state::{BankOutcome, BankTierRisk, NegativeOutcome, RoleType, TOTAL_ODDS};
+use heist_config::Config;
+use heist_sim::{simulate, strategy::Strategy, SimConfig};
+use std::path::Path;
+
+#[test]
+fn flat_table_pays_the_bank_rate() {
+    let mut config = SimConfig::with_default_tables(100, 24 * 10);
+    config.tables[BankTierRisk::Low as usize].outcomes = vec![BankOutcome::new(
+        false,
+        NegativeOutcome::None,
+        TOTAL_ODDS,
+        1,
+    )];
+    config.strategies = vec![Strategy::Fixed {
+        tier: BankTierRisk::Low,
+        hold_hours: 24,
+    }];
+    config.players = 10;
+    config.runs = 5;
+    // Covers every player for the whole season, no stake waits on the vault
+    config.vault_balance = 10 * 100 * 24 * 10;
+
+    let report = simulate(&config);
+    let low = &report.tiers[BankTierRisk::Low as usize];
+
+    assert_eq!(report.rejection_rate, 0.0);
+    assert_eq!(low.settlements, 10 * 10 * 5);
+    assert_eq!(low.mean_payout, 2_400.0);
+    assert_eq!(low.variance, 0.0);
+    assert_eq!(low.return_to_player, 1.0);
+    assert_eq!(report.vault_drain.last(), Some(&(10 * 10 * 2_400)));
+}
+
+#[test]
+fn same_seed_same_report() {
+    let config = SimConfig::with_default_tables(100, 24 * 30);
+    let first = simulate(&config);
+    let second = simulate(&config);
+
+    assert_eq!(first.vault_drain, second.vault_drain);
+    assert_eq!(first.insolvency_probability, second.insolvency_probability);
+}
+
+#[test]
+fn roles_boost_and_shift_the_roll() {
+    let mut config = SimConfig::with_default_tables(100, 24 * 10);
+    config.tables[BankTierRisk::Low as usize].outcomes = vec![
+        BankOutcome::new(false, NegativeOutcome::None, TOTAL_ODDS / 2, 2),
+        BankOutcome::new(true, NegativeOutcome::Fumbled, TOTAL_ODDS / 2, 0),
+    ];
+    let gorrila = &mut config.roles[RoleType::Gorrila as usize];
+    gorrila.reward_boost_bps = 1_000;
+    gorrila.odds_shift = TOTAL_ODDS / 2;
+    config.player_roles = vec![RoleType::Gorrila];
+    config.strategies = vec![Strategy::Fixed {
+        tier: BankTierRisk::Low,
+        hold_hours: 24,
+    }];
+    config.players = 10;
+    config.runs = 5;
+
+    let report = simulate(&config);
+    let low = &report.tiers[BankTierRisk::Low as usize];
+
+    assert_eq!(low.mean_payout, 2.0 * 2_640.0);
+    assert_eq!(low.negative_rate, 0.0);
+}
+
+#[test]
+fn stakes_wait_for_the_vault_to_cover_them() {
+    let mut config = SimConfig::with_default_tables(100, 24 * 30);
+    config.runs = 5;
+
+    let report = simulate(&config);
+
+    // A thousand players commit more than init funds, the rest are turned away
+    assert!(report.rejection_rate > 0.0);
+    assert_eq!(report.insolvency_probability, 0.0);
+    assert_eq!(report.mean_insolvency_day(), None);
+}
+
+#[test]
+fn config_vault_is_funded_like_init() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../heist-config/pool.example.toml");
+    let config = SimConfig::from_config(&Config::load(&path).unwrap(), 24 * 30);
+
+    // The High bank's own rate is not part of what init deposits
+    assert_eq!(config.vault_balance, 3 * 100 * 24 * 30);
+    assert!(!config.tables[BankTierRisk::High as usize].allowed_roles[RoleType::Chimp as usize]);
+}
@@ -0,0 +1,1131 @@
+#![no_main]
+
+use anchor_lang::{
+    prelude::{AccountInfo, Clock, ProgramResult, Pubkey},
+    AccountDeserialize, AccountSerialize, Id,
+};
+use arbitrary::Arbitrary;
+use heist::{economics::*, merkle::*, state::*};
+use heist_client::{decode, instructions, instructions::CompressedLeaf, pda::*};
+use heist_fixtures::{NftBuilder, NftFixture, TreeFixture};
+use libfuzzer_sys::fuzz_target;
+use mpl_bubblegum::state::{
+    leaf_schema::LeafSchema,
+    metaplex_adapter::{Collection, Creator, MetadataArgs, TokenProgramVersion, TokenStandard},
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    keccak,
+    native_token::LAMPORTS_PER_SOL,
+    program_option::COption,
+    program_pack::Pack,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    signer::keypair::keypair_from_seed,
+    system_program,
+    transaction::Transaction,
+};
+use spl_account_compression::Noop;
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+const PLAYERS: usize = 4;
+const NFTS_PER_PLAYER: usize = 2;
+const REWARD_PER_HOUR: u64 = 10;
+const SEASON: u64 = 10 * 24 * 3600;
+// Fixed so the same input always rolls the same outcomes
+const GENESIS_TIME: i64 = 1_700_000_000;
+const CREATOR_FUNDS: u64 = u64::MAX / 2;
+const MAX_ACTIONS: usize = 64;
+const MERKLE_TREE: u8 = 8;
+
+#[derive(Arbitrary, Debug)]
+enum Action {
+    // Opens the pool the first time, tops up the vault after
+    Init,
+    Stake {
+        player: u8,
+        nft: u8,
+        bank: u8,
+    },
+    // Settles and pays the reward, there is no separate claim
+    Unstake {
+        player: u8,
+        nft: u8,
+    },
+    EmergencyUnstake {
+        player: u8,
+        nft: u8,
+    },
+    ForceUnstake {
+        player: u8,
+        nft: u8,
+        forfeit_rewards: bool,
+    },
+    // Each player owns one compressed NFT
+    StakeCompressed {
+        player: u8,
+        bank: u8,
+    },
+    UnstakeCompressed {
+        player: u8,
+    },
+    EmergencyUnstakeCompressed {
+        player: u8,
+    },
+    ForceUnstakeCompressed {
+        player: u8,
+        forfeit_rewards: bool,
+    },
+    // Hands the NFT to an outsider, or back, leaving its stake stale while away
+    MoveNft {
+        player: u8,
+        nft: u8,
+    },
+    Crank {
+        player: u8,
+        nft: u8,
+    },
+    // Writes a stake as the program left it before stakes were keyed by mint
+    InjectLegacyStake {
+        player: u8,
+        nft: u8,
+        bank: u8,
+    },
+    Migrate {
+        player: u8,
+        nft: u8,
+    },
+    QueueChange {
+        change: ChangeAction,
+    },
+    // Picks among the changes queued so far
+    ExecuteChange {
+        change_id: u8,
+    },
+    Block {
+        player: u8,
+    },
+    Unblock {
+        player: u8,
+    },
+    Pause,
+    Unpause,
+    // Creator tops the vault up
+    Fund {
+        amount: u32,
+    },
+    // Share of the whole vault out of u8::MAX, the program has to refuse
+    // whatever live stakes could still claim
+    Withdraw {
+        share: u8,
+    },
+    Wait {
+        hours: u8,
+    },
+}
+
+#[derive(Arbitrary, Debug)]
+enum ChangeAction {
+    // The live table with its positive multipliers raised, odds kept
+    Bank {
+        bank: u8,
+        reward_per_hour: u8,
+        multiplier_boost: u8,
+    },
+    Role {
+        gorrila: bool,
+        reward_boost_bps: u16,
+        odds_shift: u16,
+    },
+    ChangeDelay {
+        hours: u8,
+    },
+    RtpBounds {
+        min_rtp_bps: u16,
+        max_rtp_bps: u16,
+    },
+}
+
+struct Player {
+    keypair: Keypair,
+    nfts: Vec<NftFixture>,
+    role_proofs: Vec<RoleProof>,
+    compressed: CompressedNft,
+}
+
+// Minted into the shared tree at the player's index, nonce and index match
+struct CompressedNft {
+    index: u32,
+    asset_id: Pubkey,
+    metadata: MetadataArgs,
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    role_proof: RoleProof,
+}
+
+struct Harness {
+    context: ProgramTestContext,
+    creator: Keypair,
+    creator_nft: NftFixture,
+    collection: NftFixture,
+    reward_mint: Pubkey,
+    role_merkle_root: [u8; 32],
+    players: Vec<Player>,
+    // Receives moved NFTs
+    outsider: Keypair,
+    // Mirrors the leaves Bubblegum rewrites so proofs stay current
+    tree: TreeFixture,
+}
+
+fuzz_target!(|actions: Vec<Action>| {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let mut harness = Harness::start().await;
+        for action in actions.iter().take(MAX_ACTIONS) {
+            harness.apply(action).await;
+            harness.check_invariants().await;
+        }
+    });
+});
+
+impl Harness {
+    async fn start() -> Harness {
+        let mut program_test = ProgramTest::new("heist", heist::ID, processor!(heist::entry));
+        program_test.add_program(
+            "mpl_bubblegum",
+            mpl_bubblegum::ID,
+            processor!(mpl_bubblegum::entry),
+        );
+        program_test.add_program(
+            "spl_account_compression",
+            spl_account_compression::ID,
+            processor!(spl_account_compression::entry),
+        );
+        program_test.add_program("spl_noop", Noop::id(), processor!(noop));
+
+        let creator = keypair(0);
+        let collection = NftBuilder::new(creator.pubkey()).mint(address(1)).build();
+        let creator_nft = NftBuilder::new(creator.pubkey())
+            .mint(address(2))
+            .creator(creator.pubkey(), true, 100)
+            .collection(collection.mint, true)
+            .build();
+        let reward_mint = address(3);
+        let merkle_tree = address(MERKLE_TREE);
+        collection.add_to(&mut program_test);
+        creator_nft.add_to(&mut program_test);
+
+        program_test.add_account(creator.pubkey(), system_account());
+        program_test.add_account(reward_mint, mint_account(&creator.pubkey(), CREATOR_FUNDS));
+        program_test.add_account(
+            get_player_reward_token_address(&creator.pubkey(), &reward_mint),
+            token_account(&creator.pubkey(), &reward_mint, CREATOR_FUNDS),
+        );
+        program_test.add_account(
+            get_global_reward_token_address(&reward_mint),
+            token_account(&find_global_address().0, &reward_mint, 0),
+        );
+        let outsider = keypair(PLAYERS as u8 + 1);
+        program_test.add_account(outsider.pubkey(), system_account());
+
+        let mut players = Vec::new();
+        let mut role_leaves = Vec::new();
+        let mut tree_leaves = Vec::new();
+        for index in 0..PLAYERS {
+            let keypair = keypair(index as u8 + 1);
+            program_test.add_account(keypair.pubkey(), system_account());
+            program_test.add_account(
+                get_player_reward_token_address(&keypair.pubkey(), &reward_mint),
+                token_account(&keypair.pubkey(), &reward_mint, 0),
+            );
+
+            let mut nfts = Vec::new();
+            let mut role_proofs = Vec::new();
+            for nft_index in 0..NFTS_PER_PLAYER {
+                let nft = NftBuilder::new(keypair.pubkey())
+                    .mint(address((16 + index * NFTS_PER_PLAYER + nft_index) as u8))
+                    .creator(creator.pubkey(), true, 100)
+                    .collection(collection.mint, true)
+                    .build();
+                nft.add_to(&mut program_test);
+
+                // Both roles and a spread of rarities so role gating and boosts get exercised
+                let role = if nft_index % 2 == 0 {
+                    RoleType::Chimp
+                } else {
+                    RoleType::Gorrila
+                };
+                let rarity = BASE_RARITY_WEIGHT + 2_500 * index as u16;
+                role_leaves.push(role_leaf(&nft.mint, role, rarity));
+                role_proofs.push(RoleProof {
+                    role,
+                    rarity,
+                    proof: vec![],
+                });
+                nfts.push(nft);
+            }
+
+            let compressed = compressed_nft(
+                index as u32,
+                &merkle_tree,
+                &creator.pubkey(),
+                &collection.mint,
+            );
+            role_leaves.push(role_leaf(
+                &compressed.asset_id,
+                compressed.role_proof.role,
+                compressed.role_proof.rarity,
+            ));
+            tree_leaves.push(leaf_node(&compressed, &keypair.pubkey(), &keypair.pubkey()));
+            players.push(Player {
+                keypair,
+                nfts,
+                role_proofs,
+                compressed,
+            });
+        }
+
+        // Proofs come back in the order the leaves were pushed
+        let (role_merkle_root, proofs) = merkle_tree(&role_leaves);
+        let mut proofs = proofs.into_iter();
+        for player in players.iter_mut() {
+            for role_proof in player.role_proofs.iter_mut() {
+                role_proof.proof = proofs.next().unwrap();
+            }
+            player.compressed.role_proof.proof = proofs.next().unwrap();
+        }
+        let tree = TreeFixture::new(merkle_tree, creator.pubkey(), tree_leaves);
+        tree.add_to(&mut program_test);
+
+        let mut context = program_test.start_with_context().await;
+        let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = GENESIS_TIME;
+        context.set_sysvar(&clock);
+
+        Harness {
+            context,
+            creator,
+            creator_nft,
+            collection,
+            reward_mint,
+            role_merkle_root,
+            players,
+            outsider,
+            tree,
+        }
+    }
+
+    async fn apply(&mut self, action: &Action) {
+        let creator = self.creator.pubkey();
+        match *action {
+            Action::Init => {
+                let end_date = self.now().await + SEASON;
+                let ixs = [
+                    instructions::init_or_update_collection(
+                        &creator,
+                        &self.creator_nft.token_account,
+                        &self.creator_nft.mint,
+                        &self.collection.mint,
+                        &self.reward_mint,
+                        end_date,
+                        REWARD_PER_HOUR,
+                    ),
+                    instructions::publish_role_merkle_root(&creator, self.role_merkle_root),
+                ];
+                self.process(&ixs).await;
+            }
+            Action::Stake { player, nft, bank } => {
+                let (player, nft) = (self.player(player), nft as usize % NFTS_PER_PLAYER);
+                let ix = instructions::stake_player_stake_info(
+                    &self.players[player].keypair.pubkey(),
+                    &self.players[player].nfts[nft].token_account,
+                    &self.players[player].nfts[nft].mint,
+                    &self.collection.mint,
+                    &self.reward_mint,
+                    bank_tier(bank),
+                    self.players[player].role_proofs[nft].clone(),
+                );
+                self.process(&[ix]).await;
+            }
+            Action::Unstake { player, nft } => {
+                let (player, nft) = (self.player(player), nft as usize % NFTS_PER_PLAYER);
+                let ix = instructions::unstake_player_stake_info(
+                    &self.players[player].keypair.pubkey(),
+                    &self.players[player].nfts[nft].token_account,
+                    &self.players[player].nfts[nft].mint,
+                    &self.collection.mint,
+                    &self.reward_mint,
+                );
+                self.process(&[ix]).await;
+            }
+            Action::EmergencyUnstake { player, nft } => {
+                let (player, nft) = (self.player(player), nft as usize % NFTS_PER_PLAYER);
+                let ix = instructions::emergency_unstake(
+                    &self.players[player].keypair.pubkey(),
+                    &self.players[player].nfts[nft].mint,
+                    &self.players[player].nfts[nft].token_account,
+                );
+                self.process(&[ix]).await;
+            }
+            Action::ForceUnstake {
+                player,
+                nft,
+                forfeit_rewards,
+            } => {
+                let (player, nft) = (self.player(player), nft as usize % NFTS_PER_PLAYER);
+                let ix = instructions::force_unstake(
+                    &creator,
+                    &self.players[player].keypair.pubkey(),
+                    &self.players[player].nfts[nft].mint,
+                    &self.players[player].nfts[nft].token_account,
+                    &self.reward_mint,
+                    forfeit_rewards,
+                );
+                self.process(&[ix]).await;
+            }
+            Action::StakeCompressed { player, bank } => {
+                let player = self.player(player);
+                let (owner, nft) = (
+                    self.players[player].keypair.pubkey(),
+                    &self.players[player].compressed,
+                );
+                let ix = instructions::stake_compressed_player_stake_info(
+                    &owner,
+                    &self.reward_mint,
+                    &self.compressed_leaf(nft.index),
+                    bank_tier(bank),
+                    nft.metadata.clone(),
+                    nft.role_proof.clone(),
+                );
+                // Staking hands the leaf delegate to the stake account
+                let delegate = find_player_stake_info_address(&owner, &nft.asset_id).0;
+                if self.process(&[ix]).await {
+                    self.set_leaf_delegate(player, &delegate);
+                }
+            }
+            Action::UnstakeCompressed { player } => {
+                let player = self.player(player);
+                let (owner, nft) = (
+                    self.players[player].keypair.pubkey(),
+                    &self.players[player].compressed,
+                );
+                let ix = instructions::unstake_compressed_player_stake_info(
+                    &owner,
+                    &self.reward_mint,
+                    &self.compressed_leaf(nft.index),
+                    nft.data_hash,
+                    nft.creator_hash,
+                );
+                if self.process(&[ix]).await {
+                    self.set_leaf_delegate(player, &owner);
+                }
+            }
+            Action::EmergencyUnstakeCompressed { player } => {
+                let player = self.player(player);
+                let (owner, nft) = (
+                    self.players[player].keypair.pubkey(),
+                    &self.players[player].compressed,
+                );
+                let ix = instructions::emergency_unstake_compressed(
+                    &owner,
+                    &self.compressed_leaf(nft.index),
+                    nft.data_hash,
+                    nft.creator_hash,
+                );
+                if self.process(&[ix]).await {
+                    self.set_leaf_delegate(player, &owner);
+                }
+            }
+            Action::ForceUnstakeCompressed {
+                player,
+                forfeit_rewards,
+            } => {
+                let player = self.player(player);
+                let (owner, nft) = (
+                    self.players[player].keypair.pubkey(),
+                    &self.players[player].compressed,
+                );
+                let ix = instructions::force_unstake_compressed(
+                    &creator,
+                    &owner,
+                    &self.reward_mint,
+                    &self.compressed_leaf(nft.index),
+                    nft.data_hash,
+                    nft.creator_hash,
+                    forfeit_rewards,
+                );
+                if self.process(&[ix]).await {
+                    self.set_leaf_delegate(player, &owner);
+                }
+            }
+            Action::MoveNft { player, nft } => {
+                let (player, nft) = (self.player(player), nft as usize % NFTS_PER_PLAYER);
+                let owner = self.players[player].keypair.pubkey();
+                let (mint, token_account) = (
+                    self.players[player].nfts[nft].mint,
+                    self.players[player].nfts[nft].token_account,
+                );
+                let outsider = self.outsider.pubkey();
+                let outsider_account = get_associated_token_address(&outsider, &mint);
+
+                let mut ixs = Vec::new();
+                if self.token_amount(&token_account).await == 1 {
+                    if self.account(&outsider_account).await.is_none() {
+                        ixs.push(create_associated_token_account(
+                            &self.context.payer.pubkey(),
+                            &outsider,
+                            &mint,
+                        ));
+                    }
+                    ixs.push(nft_transfer(&token_account, &outsider_account, &owner));
+                } else {
+                    ixs.push(nft_transfer(&outsider_account, &token_account, &outsider));
+                }
+                self.process(&ixs).await;
+            }
+            Action::Crank { player, nft } => {
+                let (player, nft) = (self.player(player), nft as usize % NFTS_PER_PLAYER);
+                let owner = self.players[player].keypair.pubkey();
+                let (mint, token_account) = (
+                    self.players[player].nfts[nft].mint,
+                    self.players[player].nfts[nft].token_account,
+                );
+                // Whichever account holds the NFT now, the crank has to tell it moved
+                let holder = if self.token_amount(&token_account).await == 1 {
+                    token_account
+                } else {
+                    get_associated_token_address(&self.outsider.pubkey(), &mint)
+                };
+                let ix = instructions::crank_stale_stake(
+                    &self.context.payer.pubkey(),
+                    &owner,
+                    &mint,
+                    &holder,
+                );
+                self.process(&[ix]).await;
+            }
+            Action::InjectLegacyStake { player, nft, bank } => {
+                let (player, nft) = (self.player(player), nft as usize % NFTS_PER_PLAYER);
+                self.inject_legacy_stake(player, nft, bank_tier(bank)).await;
+            }
+            Action::Migrate { player, nft } => {
+                let (player, nft) = (self.player(player), nft as usize % NFTS_PER_PLAYER);
+                let ix = instructions::migrate_stake_info(
+                    &self.players[player].keypair.pubkey(),
+                    &self.players[player].nfts[nft].token_account,
+                    &self.players[player].nfts[nft].mint,
+                    &self.reward_mint,
+                );
+                self.process(&[ix]).await;
+            }
+            Action::QueueChange { ref change } => {
+                let global = match self.global().await {
+                    Some(global) => global,
+                    None => return,
+                };
+                let ix = instructions::queue_change(
+                    &creator,
+                    global.change_nonce,
+                    config_change(&global, change),
+                );
+                self.process(&[ix]).await;
+            }
+            Action::ExecuteChange { change_id } => {
+                let change_nonce = match self.global().await {
+                    Some(global) => global.change_nonce,
+                    None => return,
+                };
+                let change_id = u64::from(change_id) % change_nonce.max(1);
+                let ix = instructions::execute_change(&creator, change_id, &self.reward_mint);
+                self.process(&[ix]).await;
+            }
+            Action::Block { player } => {
+                let player = self.players[self.player(player)].keypair.pubkey();
+                self.process(&[instructions::block_player(&creator, player)])
+                    .await;
+            }
+            Action::Unblock { player } => {
+                let player = self.players[self.player(player)].keypair.pubkey();
+                self.process(&[instructions::unblock_player(&creator, player)])
+                    .await;
+            }
+            Action::Pause => {
+                self.process(&[instructions::pause(&creator)]).await;
+            }
+            Action::Unpause => {
+                self.process(&[instructions::unpause(&creator)]).await;
+            }
+            Action::Fund { amount } => {
+                let ix = spl_token::instruction::transfer(
+                    &spl_token::ID,
+                    &get_player_reward_token_address(&creator, &self.reward_mint),
+                    &get_global_reward_token_address(&self.reward_mint),
+                    &creator,
+                    &[],
+                    u64::from(amount),
+                )
+                .unwrap();
+                self.process(&[ix]).await;
+            }
+            Action::Withdraw { share } => {
+                let vault_balance = self.vault_balance().await;
+                let amount =
+                    (u128::from(vault_balance) * u128::from(share) / u128::from(u8::MAX)) as u64;
+                let ix = instructions::withdraw_treasury(
+                    &creator,
+                    &self.reward_mint,
+                    &get_player_reward_token_address(&creator, &self.reward_mint),
+                    amount,
+                );
+                self.process(&[ix]).await;
+            }
+            Action::Wait { hours } => {
+                let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+                clock.unix_timestamp += i64::from(hours) * 3600;
+                self.context.set_sysvar(&clock);
+            }
+        }
+    }
+
+    // Writes what the program used to leave behind: a stake keyed by the token
+    // account, delegated the NFT and counted in the bank, with no weight yet
+    async fn inject_legacy_stake(&mut self, player: usize, nft: usize, bank: BankTierRisk) {
+        let mut global = match self.global().await {
+            Some(global) => global,
+            None => return,
+        };
+        let owner = self.players[player].keypair.pubkey();
+        let (mint, token_account) = (
+            self.players[player].nfts[nft].mint,
+            self.players[player].nfts[nft].token_account,
+        );
+        let legacy_address = find_legacy_player_stake_info_address(&owner, &token_account).0;
+        let stake_address = find_player_stake_info_address(&owner, &mint).0;
+        if self.account(&legacy_address).await.is_some()
+            || self.account(&stake_address).await.is_some()
+        {
+            return;
+        }
+        let mut nft_account = match self.account(&token_account).await {
+            Some(account) => account,
+            None => return,
+        };
+        let mut token_state = TokenAccount::unpack(&nft_account.data).unwrap();
+        if token_state.amount != 1 {
+            return;
+        }
+
+        let stake = LegacyPlayerStakeInfo {
+            owner,
+            mint,
+            bank,
+            stake_start_time: self.now().await,
+            role: self.players[player].role_proofs[nft].role,
+        };
+        self.set_program_account(&legacy_address, &stake, LegacyPlayerStakeInfo::len());
+
+        token_state.delegate = COption::Some(legacy_address);
+        token_state.delegated_amount = 1;
+        TokenAccount::pack(token_state, &mut nft_account.data).unwrap();
+        self.context
+            .set_account(&token_account, &nft_account.into());
+
+        let mut player_info = self.player_info(&owner).await.unwrap_or(PlayerInfo {
+            is_initialized: true,
+            point_balance: 0,
+            active_staked: 0,
+            jail_end_time: 0,
+        });
+        if player_info.active_staked == 0 {
+            global.total_player += 1;
+        }
+        player_info.active_staked += 1;
+        self.set_program_account(
+            &find_player_info_address(&owner).0,
+            &player_info,
+            PlayerInfo::len(),
+        );
+
+        global.bank_mut(bank).total_staked += 1;
+        let global_address = find_global_address().0;
+        let mut global_account = self.account(&global_address).await.unwrap();
+        global_account.data[8..Global::len()].copy_from_slice(bytemuck::bytes_of(&global));
+        self.context
+            .set_account(&global_address, &global_account.into());
+    }
+
+    fn set_program_account<T: AccountSerialize>(
+        &mut self,
+        address: &Pubkey,
+        state: &T,
+        len: usize,
+    ) {
+        let mut data = Vec::with_capacity(len);
+        state.try_serialize(&mut data).unwrap();
+        data.resize(len, 0);
+        let account = Account {
+            lamports: Rent::default().minimum_balance(len),
+            data,
+            owner: heist::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.context.set_account(address, &account.into());
+    }
+
+    async fn check_invariants(&mut self) {
+        let global = match self.global().await {
+            Some(global) => global,
+            None => return,
+        };
+
+        let mut staked = [0u64; MAX_BANKS];
+        let mut weight = [0u64; MAX_BANKS];
+        let mut active_players = 0;
+        for player in 0..PLAYERS {
+            let owner = self.players[player].keypair.pubkey();
+            let stakes = self.stakes(player).await;
+            let legacy_stakes = self.legacy_stakes(player).await;
+            let active_staked = match self.player_info(&owner).await {
+                Some(player_info) => player_info.active_staked,
+                None => 0,
+            };
+            assert_eq!(
+                usize::from(active_staked),
+                stakes.len() + legacy_stakes.len(),
+                "active_staked of {} does not match its stakes",
+                owner
+            );
+
+            if active_staked > 0 {
+                active_players += 1;
+            }
+            for stake in stakes {
+                staked[stake.bank as usize] += 1;
+                weight[stake.bank as usize] += u64::from(stake.rarity);
+            }
+            // Legacy stakes are counted but only earn once migrated
+            for stake in legacy_stakes {
+                staked[stake.bank as usize] += 1;
+            }
+        }
+
+        let banks = global.banks;
+        for (index, bank) in banks.iter().enumerate() {
+            let (total_staked, total_weight) = (bank.total_staked, bank.total_weight());
+            assert_eq!(
+                total_staked, staked[index],
+                "total_staked of bank {}",
+                index
+            );
+            assert_eq!(
+                total_weight, weight[index],
+                "total_weight of bank {}",
+                index
+            );
+        }
+
+        let total_player = global.total_player;
+        assert!(
+            total_player <= PLAYERS as u64,
+            "total_player underflowed to {}",
+            total_player
+        );
+        assert_eq!(total_player, active_players, "total_player");
+
+        let vault_balance = self.vault_balance().await;
+        let (committed, liabilities) = (global.committed_rewards(), self.liabilities().await);
+        assert!(
+            vault_balance >= committed,
+            "vault holds {} but the program committed {}",
+            vault_balance,
+            committed
+        );
+        assert!(
+            committed >= liabilities,
+            "program committed {} but stakes could claim {}",
+            committed,
+            liabilities
+        );
+    }
+
+    // Only transaction errors are expected, anything else means the program crashed.
+    // Returns whether the transaction went through
+    async fn process(&mut self, instructions: &[Instruction]) -> bool {
+        let blockhash = self
+            .context
+            .banks_client
+            .get_new_latest_blockhash(&self.context.last_blockhash)
+            .await
+            .unwrap();
+        self.context.last_blockhash = blockhash;
+
+        // Every known key an instruction wants a signature from signs
+        let mut signers = vec![&self.context.payer];
+        let keypairs = std::iter::once(&self.creator)
+            .chain(self.players.iter().map(|player| &player.keypair))
+            .chain(std::iter::once(&self.outsider));
+        for keypair in keypairs {
+            if needs_signature(instructions, &keypair.pubkey()) {
+                signers.push(keypair);
+            }
+        }
+        let mut transaction =
+            Transaction::new_with_payer(instructions, Some(&self.context.payer.pubkey()));
+        transaction.sign(&signers, blockhash);
+
+        match self
+            .context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+        {
+            Ok(()) => true,
+            Err(BanksClientError::TransactionError(_)) => false,
+            Err(error) => panic!("{:?} running {:?}", error, instructions),
+        }
+    }
+
+    // What every live stake could be paid if it settled now at its bank's best
+    // multiplier. Nothing accrues past end_date
+    async fn liabilities(&mut self) -> u64 {
+        let global = match self.global().await {
+            Some(global) => global,
+            None => return 0,
+        };
+        let now = self.now().await.min(global.end_date);
+
+        let mut liabilities = 0u64;
+        for player in 0..PLAYERS {
+            for stake in self.stakes(player).await {
+                let bank = global.bank(stake.bank);
+                let role = global.role(stake.role);
+                let base_reward = accrued_reward(
+                    bank.reward_per_hour,
+                    role.reward_boost_bps,
+                    stake.rarity,
+                    elapsed_since(stake.stake_start_time, now),
+                );
+                liabilities =
+                    liabilities.saturating_add(payout(base_reward, bank.max_multiplier()));
+            }
+        }
+        liabilities
+    }
+
+    // Live stakes at their canonical addresses, the compressed one included
+    async fn stakes(&mut self, player: usize) -> Vec<PlayerStakeInfo> {
+        let owner = self.players[player].keypair.pubkey();
+        let mints: Vec<Pubkey> = self.players[player]
+            .nfts
+            .iter()
+            .map(|nft| nft.mint)
+            .chain(std::iter::once(self.players[player].compressed.asset_id))
+            .collect();
+
+        let mut stakes = Vec::new();
+        for mint in mints {
+            let address = find_player_stake_info_address(&owner, &mint).0;
+            if let Some(account) = self.account(&address).await {
+                stakes.push(decode::player_stake_info(&account.data).unwrap());
+            }
+        }
+        stakes
+    }
+
+    async fn legacy_stakes(&mut self, player: usize) -> Vec<LegacyPlayerStakeInfo> {
+        let owner = self.players[player].keypair.pubkey();
+        let token_accounts: Vec<Pubkey> = self.players[player]
+            .nfts
+            .iter()
+            .map(|nft| nft.token_account)
+            .collect();
+
+        let mut stakes = Vec::new();
+        for token_account in token_accounts {
+            let address = find_legacy_player_stake_info_address(&owner, &token_account).0;
+            if let Some(account) = self.account(&address).await {
+                stakes
+                    .push(LegacyPlayerStakeInfo::try_deserialize(&mut &account.data[..]).unwrap());
+            }
+        }
+        stakes
+    }
+
+    async fn player_info(&mut self, owner: &Pubkey) -> Option<PlayerInfo> {
+        let account = self.account(&find_player_info_address(owner).0).await?;
+        Some(decode::player_info(&account.data).unwrap())
+    }
+
+    async fn global(&mut self) -> Option<Global> {
+        let account = self.account(&find_global_address().0).await?;
+        Some(decode::global(&account.data).unwrap())
+    }
+
+    async fn vault_balance(&mut self) -> u64 {
+        let address = get_global_reward_token_address(&self.reward_mint);
+        self.token_amount(&address).await
+    }
+
+    // Zero for token accounts that do not exist
+    async fn token_amount(&mut self, address: &Pubkey) -> u64 {
+        match self.account(address).await {
+            Some(account) => TokenAccount::unpack(&account.data).unwrap().amount,
+            None => 0,
+        }
+    }
+
+    // Accounts the program closed read back as missing or drained
+    async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .filter(|account| account.lamports > 0)
+    }
+
+    async fn now(&mut self) -> u64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp as u64
+    }
+
+    fn compressed_leaf(&self, index: u32) -> CompressedLeaf {
+        CompressedLeaf {
+            merkle_tree: self.tree.merkle_tree,
+            nonce: u64::from(index),
+            index,
+            root: self.tree.root(),
+            proof: self.tree.proof(index),
+        }
+    }
+
+    // The owner never changes, only who holds the leaf delegate
+    fn set_leaf_delegate(&mut self, player: usize, delegate: &Pubkey) {
+        let owner = self.players[player].keypair.pubkey();
+        let nft = &self.players[player].compressed;
+        self.tree
+            .set_leaf(nft.index, leaf_node(nft, &owner, delegate));
+    }
+
+    fn player(&self, player: u8) -> usize {
+        usize::from(player) % PLAYERS
+    }
+}
+
+// Stands in for the noop program Bubblegum logs leaf changes through
+fn noop(_program_id: &Pubkey, _accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+fn compressed_nft(
+    index: u32,
+    merkle_tree: &Pubkey,
+    creator: &Pubkey,
+    collection_mint: &Pubkey,
+) -> CompressedNft {
+    let metadata = MetadataArgs {
+        name: format!("Compressed #{}", index),
+        symbol: "HEIST".to_string(),
+        uri: String::new(),
+        seller_fee_basis_points: 500,
+        primary_sale_happened: true,
+        is_mutable: false,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: Some(Collection {
+            verified: true,
+            key: *collection_mint,
+        }),
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators: vec![Creator {
+            address: *creator,
+            verified: true,
+            share: 100,
+        }],
+    };
+    CompressedNft {
+        index,
+        asset_id: get_asset_id(merkle_tree, u64::from(index)),
+        data_hash: hash_metadata(&metadata).unwrap(),
+        creator_hash: hash_creators(&metadata.creators),
+        metadata,
+        role_proof: RoleProof {
+            role: RoleType::Gorrila,
+            rarity: BASE_RARITY_WEIGHT + 1_000 * index as u16,
+            proof: vec![],
+        },
+    }
+}
+
+fn leaf_node(nft: &CompressedNft, owner: &Pubkey, delegate: &Pubkey) -> [u8; 32] {
+    LeafSchema::new_v0(
+        nft.asset_id,
+        *owner,
+        *delegate,
+        u64::from(nft.index),
+        nft.data_hash,
+        nft.creator_hash,
+    )
+    .to_node()
+}
+
+fn nft_transfer(source: &Pubkey, destination: &Pubkey, owner: &Pubkey) -> Instruction {
+    spl_token::instruction::transfer(&spl_token::ID, source, destination, owner, &[], 1).unwrap()
+}
+
+// Changes stay within what queue_change accepts most of the time, execute then
+// has to keep the vault solvent
+fn config_change(global: &Global, change: &ChangeAction) -> ConfigChange {
+    match *change {
+        ChangeAction::Bank {
+            bank,
+            reward_per_hour,
+            multiplier_boost,
+        } => {
+            let bank_tier_risk = bank_tier(bank);
+            let bank_outcomes = global
+                .bank(bank_tier_risk)
+                .outcomes()
+                .iter()
+                .map(|outcome| BankOutcomeArgs {
+                    odds: outcome.odds,
+                    payout_multiplier: if outcome.is_negative() {
+                        outcome.payout_multiplier
+                    } else {
+                        outcome
+                            .payout_multiplier
+                            .saturating_add(multiplier_boost % 4)
+                    },
+                    is_negative: outcome.is_negative(),
+                    negative_outcome: outcome.negative_outcome(),
+                })
+                .collect();
+            ConfigChange::UpdateBank {
+                bank_tier_risk,
+                reward_per_hour: u64::from(reward_per_hour),
+                bank_outcomes,
+            }
+        }
+        ChangeAction::Role {
+            gorrila,
+            reward_boost_bps,
+            odds_shift,
+        } => ConfigChange::UpdateRole {
+            role_type: if gorrila {
+                RoleType::Gorrila
+            } else {
+                RoleType::Chimp
+            },
+            reward_boost_bps,
+            odds_shift: u32::from(odds_shift),
+            jail_time_reduction: 0,
+        },
+        ChangeAction::ChangeDelay { hours } => ConfigChange::UpdateChangeDelay {
+            change_delay: u64::from(hours) * 3600,
+        },
+        ChangeAction::RtpBounds {
+            min_rtp_bps,
+            max_rtp_bps,
+        } => ConfigChange::UpdateRtpBounds {
+            min_rtp_bps: u32::from(min_rtp_bps),
+            max_rtp_bps: u32::from(max_rtp_bps),
+        },
+    }
+}
+
+fn needs_signature(instructions: &[Instruction], signer: &Pubkey) -> bool {
+    instructions.iter().any(|ix| {
+        ix.accounts
+            .iter()
+            .any(|meta| meta.is_signer && meta.pubkey == *signer)
+    })
+}
+
+fn bank_tier(bank: u8) -> BankTierRisk {
+    match bank % 3 {
+        0 => BankTierRisk::Low,
+        1 => BankTierRisk::Mid,
+        _ => BankTierRisk::High,
+    }
+}
+
+// Root and one proof per leaf, pairs hashed in sorted order like merkle::verify_proof.
+// An odd node out moves up a level unhashed
+fn merkle_tree(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let mut proofs = vec![Vec::new(); leaves.len()];
+    let mut positions: Vec<usize> = (0..leaves.len()).collect();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        for (leaf, position) in positions.iter_mut().enumerate() {
+            if let Some(sibling) = level.get(*position ^ 1) {
+                proofs[leaf].push(*sibling);
+            }
+            *position /= 2;
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] if left <= right => keccak::hashv(&[left, right]).0,
+                [left, right] => keccak::hashv(&[right, left]).0,
+                [node] => *node,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    (level[0], proofs)
+}
+
+fn keypair(seed: u8) -> Keypair {
+    keypair_from_seed(&[seed; 32]).unwrap()
+}
+
+fn address(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+fn system_account() -> Account {
+    Account::new(100 * LAMPORTS_PER_SOL, 0, &system_program::ID)
+}
+
+fn mint_account(mint_authority: &Pubkey, supply: u64) -> Account {
+    packed_account(Mint {
+        mint_authority: COption::Some(*mint_authority),
+        supply,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    })
+}
+
+fn token_account(owner: &Pubkey, mint: &Pubkey, amount: u64) -> Account {
+    packed_account(TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    })
+}
+
+fn packed_account<T: Pack>(state: T) -> Account {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,165 @@
+use crate::state::{Bank, BankOutcomeArgs, BPS_DENOMINATOR, MAX_BANKS, TOTAL_ODDS};
+use anchor_lang::prelude::Pubkey;
+
+// Reward earned before the outcome multiplier, only whole hours count.
+// Saturates instead of overflowing so settlement can never panic
+pub fn accrued_reward(
+    reward_per_hour: u64,
+    reward_boost_bps: u16,
+    rarity: u16,
+    elapsed_seconds: u64,
+) -> u64 {
+    let bps = u128::from(BPS_DENOMINATOR);
+    let reward_per_hour = u128::from(reward_per_hour) * (bps + u128::from(reward_boost_bps)) / bps;
+    let reward = u128::from(elapsed_seconds / 3600)
+        .saturating_mul(reward_per_hour)
+        .saturating_mul(u128::from(rarity))
+        / bps;
+    u64::try_from(reward).unwrap_or(u64::MAX)
+}
+
+// What stakes of one role in a bank can earn by end_date at the best multiplier,
+// from their summed rarity and rarity weighted start times. Hours are not
+// floored and the total is rounded up, so it covers every stake's payout
+pub fn committed_reward(
+    reward_per_hour: u64,
+    reward_boost_bps: u16,
+    max_multiplier: u8,
+    role_weight: u64,
+    role_weighted_start: u128,
+    end_date: u64,
+) -> u64 {
+    let bps = u128::from(BPS_DENOMINATOR);
+    let weighted_seconds = u128::from(role_weight)
+        .saturating_mul(u128::from(end_date))
+        .saturating_sub(role_weighted_start);
+    let numerator = weighted_seconds
+        .saturating_mul(u128::from(reward_per_hour))
+        .saturating_mul(bps + u128::from(reward_boost_bps))
+        .saturating_mul(u128::from(max_multiplier));
+    let denominator = 3600 * bps * bps;
+    let committed = numerator / denominator + u128::from(numerator % denominator != 0);
+    u64::try_from(committed).unwrap_or(u64::MAX)
+}
+
+// What init deposits, every bank at the pool rate for each started hour
+pub fn season_funding(reward_per_hour: u64, seconds_to_end: u64) -> u64 {
+    let hours_to_end = (seconds_to_end + 3599) / 3600;
+    hours_to_end * MAX_BANKS as u64 * reward_per_hour
+}
+
+// Clock can lag a stake made in the same slot, that counts as no time staked
+pub fn elapsed_since(stake_start_time: u64, current_time: u64) -> u64 {
+    current_time.saturating_sub(stake_start_time)
+}
+
+pub fn payout(base_reward: u64, payout_multiplier: u8) -> u64 {
+    base_reward.saturating_mul(u64::from(payout_multiplier))
+}
+
+// Pseudo random roll in 0..TOTAL_ODDS from the settlement time and owner.
+// LCG over 32 bits, scaled down to the odds range
+pub fn roll(unix_timestamp: u64, owner: &Pubkey) -> u64 {
+    const A: u64 = 1664525;
+    const C: u64 = 1013904223;
+    const M: u64 = 1 << 32;
+
+    let seed = unix_timestamp
+        ^ owner
+            .as_ref()
+            .iter()
+            .fold(0u64, |acc, &x| acc + u64::from(x));
+    let value = A.wrapping_mul(seed).wrapping_add(C) % M;
+
+    value * u64::from(TOTAL_ODDS) / M
+}
+
+// The role's odds shift moves the roll towards the first outcome
+pub fn shifted_roll(roll: u64, odds_shift: u32) -> u64 {
+    roll.saturating_sub(u64::from(odds_shift))
+}
+
+// Odds are slices of the roll laid end to end in table order. A roll past
+// the last slice lands on the last outcome, None only for an empty table
+pub fn select_outcome(odds: impl IntoIterator<Item = u32>, roll: u64) -> Option<usize> {
+    let mut threshold = 0u64;
+    let mut last = None;
+    for (index, odds) in odds.into_iter().enumerate() {
+        threshold += u64::from(odds);
+        if roll < threshold {
+            return Some(index);
+        }
+        last = Some(index);
+    }
+    last
+}
+
+// Odds each outcome actually gets once odds_shift is taken off the roll,
+// rolls pushed below zero all land on the first outcome
+pub fn shifted_odds(odds: impl IntoIterator<Item = u32>, odds_shift: u32) -> Vec<u32> {
+    let mut end = 0u32;
+    let mut shifted_end = 0u32;
+    odds.into_iter()
+        .map(|odds| {
+            let previous = shifted_end;
+            end = end.saturating_add(odds);
+            shifted_end = end.saturating_add(odds_shift).min(TOTAL_ODDS);
+            shifted_end - previous
+        })
+        .collect()
+}
+
+// Exact figures for one bank table, shared by the program and off-chain tools
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TableStats {
+    // Sum of odds * payout_multiplier, over TOTAL_ODDS it is the expected multiplier
+    pub weighted_multiplier: u64,
+    // Odds of an outcome that pays nothing
+    pub ruin_odds: u64,
+}
+
+impl TableStats {
+    // (odds, payout_multiplier) pairs
+    pub fn from_outcomes(outcomes: impl IntoIterator<Item = (u32, u8)>) -> TableStats {
+        outcomes
+            .into_iter()
+            .fold(TableStats::default(), |stats, (odds, payout_multiplier)| {
+                TableStats {
+                    weighted_multiplier: stats.weighted_multiplier
+                        + u64::from(odds) * u64::from(payout_multiplier),
+                    ruin_odds: stats.ruin_odds
+                        + if payout_multiplier == 0 {
+                            u64::from(odds)
+                        } else {
+                            0
+                        },
+                }
+            })
+    }
+
+    pub fn from_args(bank_outcomes: &[BankOutcomeArgs]) -> TableStats {
+        TableStats::from_outcomes(bank_outcomes.iter().map(|o| (o.odds, o.payout_multiplier)))
+    }
+
+    pub fn from_bank(bank: &Bank) -> TableStats {
+        TableStats::from_outcomes(
+            bank.outcomes()
+                .iter()
+                .map(|o| (o.odds, o.payout_multiplier)),
+        )
+    }
+
+    pub fn expected_multiplier(&self) -> f64 {
+        self.weighted_multiplier as f64 / f64::from(TOTAL_ODDS)
+    }
+
+    // Paid out over accrued, 10_000 pays exactly the bank rate. Rounds down
+    pub fn rtp_bps(&self) -> u64 {
+        self.weighted_multiplier * BPS_DENOMINATOR / u64::from(TOTAL_ODDS)
+    }
+
+    // Chance a settlement loses the whole accrued reward. Rounds down
+    pub fn ruin_probability_bps(&self) -> u64 {
+        self.ruin_odds * BPS_DENOMINATOR / u64::from(TOTAL_ODDS)
+    }
+}
@@ -8,4 +8,64 @@ pub enum ErrorCode {
     InvalidMintAddress,
     #[msg("Invalid NFT not part of Collection")]
     MismatchCollection,
+    #[msg("Stake does not belong to a compressed NFT")]
+    NotCompressedStake,
+    #[msg("Stake still holds its delegated NFT")]
+    StakeNotStale,
+    #[msg("Token account does not hold the staked NFT")]
+    InvalidStaleProof,
+    #[msg("Compressed NFT stakes cannot be cranked")]
+    CompressedStake,
+    #[msg("Bank outcome odds must add up to 100_000")]
+    InvalidBankOdds,
+    #[msg("Bank has more outcomes than it can hold")]
+    TooManyBankOutcomes,
+    #[msg("Role modifier out of range")]
+    InvalidRoleModifier,
+    #[msg("Player is in jail")]
+    PlayerJailed,
+    #[msg("Role proof does not match the published root")]
+    InvalidRoleProof,
+    #[msg("Role is not allowed in this bank")]
+    RoleNotAllowedInBank,
+    #[msg("Signer is not the pending authority")]
+    InvalidPendingAuthority,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Signer is not the pool operator")]
+    InvalidOperator,
+    #[msg("Signer is not the pool treasurer")]
+    InvalidTreasurer,
+    #[msg("Signer is not the pool pauser")]
+    InvalidPauser,
+    #[msg("Change id does not match the next queued change")]
+    InvalidChangeId,
+    #[msg("Change is still timelocked")]
+    ChangeTimelocked,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Program is not paused")]
+    ProgramNotPaused,
+    #[msg("Player is blocked from staking")]
+    PlayerBlocked,
+    #[msg("Player is not blocked")]
+    PlayerNotBlocked,
+    #[msg("Blocklist is full")]
+    BlocklistFull,
+    #[msg("Bank table return-to-player is outside the pool's bounds")]
+    RtpOutOfBounds,
+    #[msg("Minimum RTP is above the maximum")]
+    InvalidRtpBounds,
+    #[msg("End date has already passed")]
+    EndDateInPast,
+    #[msg("Reward vault does not cover what live stakes can claim")]
+    InsufficientVault,
+    #[msg("Season has ended")]
+    SeasonEnded,
+    #[msg("Positive outcomes have to come before negative ones")]
+    InvalidOutcomeOrder,
+    #[msg("Player count would drop below zero")]
+    PlayerCountUnderflow,
+    #[msg("End date can only move later")]
+    EndDateShortened,
 }
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{BankTierRisk, RoleType};
+
+#[event]
+pub struct PoolInitialized {
+    pub collection: Pubkey,
+    pub update_authority: Pubkey,
+    pub reward_mint: Pubkey,
+    pub end_date: u64,
+    pub reward_per_hour: u64,
+}
+
+#[event]
+pub struct PoolUpdated {
+    pub update_authority: Pubkey,
+    pub end_date: u64,
+    // Bank rates only change through queued changes, the update just funds
+    pub reward_deposited: u64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct PermissionsUpdated {
+    pub operator: Pubkey,
+    pub treasurer: Pubkey,
+    pub pauser: Pubkey,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub treasurer: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ChangeQueued {
+    pub id: u64,
+    pub earliest_execution_time: u64,
+}
+
+#[event]
+pub struct ChangeExecuted {
+    pub id: u64,
+}
+
+#[event]
+pub struct ChangeCancelled {
+    pub id: u64,
+}
+
+#[event]
+pub struct RoleMerkleRootPublished {
+    pub role_merkle_root: [u8; 32],
+}
+
+#[event]
+pub struct ProgramPaused {
+    pub pauser: Pubkey,
+}
+
+#[event]
+pub struct ProgramUnpaused {
+    pub pauser: Pubkey,
+}
+
+#[event]
+pub struct Staked {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub bank: BankTierRisk,
+    pub role: RoleType,
+    pub rarity: u16,
+    pub is_compressed: bool,
+    pub stake_start_time: u64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub bank: BankTierRisk,
+}
+
+#[event]
+pub struct OutcomeRolled {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub bank: BankTierRisk,
+    pub roll: u64,
+    pub outcome_index: u8,
+    pub payout_multiplier: u8,
+    pub reward_amount: u64,
+}
+
+#[event]
+pub struct StakeFumbled {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct StakeConfiscated {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct PlayerArrested {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub jail_end_time: u64,
+}
+
+#[event]
+pub struct StakeRekt {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct StaleStakeCranked {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub cranker: Pubkey,
+}
+
+#[event]
+pub struct StakeMigrated {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub legacy_stake_info: Pubkey,
+}
+
+#[event]
+pub struct PlayerBlocked {
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct PlayerUnblocked {
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct StakeForceUnstaked {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub forfeit_rewards: bool,
+}
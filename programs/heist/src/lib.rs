@@ -1,6 +1,10 @@
-mod error;
+pub mod economics;
+pub mod error;
+pub mod events;
+pub mod merkle;
+pub mod pda;
 mod processor;
-mod state;
+pub mod state;
 mod validator;
 
 use processor::*;
@@ -8,6 +12,7 @@ use state::*;
 use validator::*;
 
 use anchor_lang::prelude::*;
+use mpl_bubblegum::state::metaplex_adapter::MetadataArgs;
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
@@ -24,16 +29,69 @@ pub mod heist {
         Ok(())
     }
 
-    pub fn update_collection_authority(ctx: Context<UpdateCollectionAuthority>) -> Result<()> {
-        update_collection_authority_processor(ctx)?;
+    pub fn update_permissions(
+        ctx: Context<UpdatePermissions>,
+        operator: Pubkey,
+        treasurer: Pubkey,
+        pauser: Pubkey,
+    ) -> Result<()> {
+        update_permissions_processor(ctx, operator, treasurer, pauser)?;
+        Ok(())
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        withdraw_treasury_processor(ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn queue_change(
+        ctx: Context<QueueChange>,
+        change_id: u64,
+        change: ConfigChange,
+    ) -> Result<()> {
+        queue_change_processor(ctx, change_id, change)?;
+        Ok(())
+    }
+
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        execute_change_processor(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        cancel_change_processor(ctx)?;
+        Ok(())
+    }
+
+    pub fn publish_role_merkle_root(
+        ctx: Context<PublishRoleMerkleRoot>,
+        role_merkle_root: [u8; 32],
+    ) -> Result<()> {
+        publish_role_merkle_root_processor(ctx, role_merkle_root)?;
+        Ok(())
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>) -> Result<()> {
+        propose_authority_processor(ctx)?;
+        Ok(())
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        accept_authority_processor(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+        cancel_authority_transfer_processor(ctx)?;
         Ok(())
     }
 
     pub fn stake_player_stake_info(
         ctx: Context<StakePlayerStakeInfo>,
         bank_tier_risk: BankTierRisk,
+        role_proof: RoleProof,
     ) -> Result<()> {
-        stake_player_stake_info_processor(ctx, bank_tier_risk)?;
+        stake_player_stake_info_processor(ctx, bank_tier_risk, role_proof)?;
         Ok(())
     }
 
@@ -41,4 +99,122 @@ pub mod heist {
         unstake_player_stake_info_processor(ctx)?;
         Ok(())
     }
+
+    pub fn stake_compressed_player_stake_info<'info>(
+        ctx: Context<'_, '_, '_, 'info, StakeCompressedPlayerStakeInfo<'info>>,
+        nonce: u64,
+        index: u32,
+        root: [u8; 32],
+        bank_tier_risk: BankTierRisk,
+        metadata: MetadataArgs,
+        role_proof: RoleProof,
+    ) -> Result<()> {
+        stake_compressed_player_stake_info_processor(
+            ctx,
+            nonce,
+            index,
+            root,
+            bank_tier_risk,
+            metadata,
+            role_proof,
+        )?;
+        Ok(())
+    }
+
+    pub fn unstake_compressed_player_stake_info<'info>(
+        ctx: Context<'_, '_, '_, 'info, UnstakeCompressedPlayerStakeInfo<'info>>,
+        nonce: u64,
+        index: u32,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+    ) -> Result<()> {
+        unstake_compressed_player_stake_info_processor(
+            ctx,
+            nonce,
+            index,
+            root,
+            data_hash,
+            creator_hash,
+        )?;
+        Ok(())
+    }
+
+    pub fn crank_stale_stake(ctx: Context<CrankStaleStake>) -> Result<()> {
+        crank_stale_stake_processor(ctx)?;
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        pause_processor(ctx)?;
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<Pause>) -> Result<()> {
+        unpause_processor(ctx)?;
+        Ok(())
+    }
+
+    pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
+        emergency_unstake_processor(ctx)?;
+        Ok(())
+    }
+
+    pub fn emergency_unstake_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, EmergencyUnstakeCompressed<'info>>,
+        nonce: u64,
+        index: u32,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+    ) -> Result<()> {
+        emergency_unstake_compressed_processor(ctx, nonce, index, root, data_hash, creator_hash)?;
+        Ok(())
+    }
+
+    pub fn preview_unstake(ctx: Context<PreviewUnstake>) -> Result<()> {
+        preview_unstake_processor(ctx)?;
+        Ok(())
+    }
+
+    pub fn block_player(ctx: Context<UpdateBlocklist>, player: Pubkey) -> Result<()> {
+        block_player_processor(ctx, player)?;
+        Ok(())
+    }
+
+    pub fn unblock_player(ctx: Context<UpdateBlocklist>, player: Pubkey) -> Result<()> {
+        unblock_player_processor(ctx, player)?;
+        Ok(())
+    }
+
+    pub fn force_unstake(ctx: Context<ForceUnstake>, forfeit_rewards: bool) -> Result<()> {
+        force_unstake_processor(ctx, forfeit_rewards)?;
+        Ok(())
+    }
+
+    pub fn force_unstake_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, ForceUnstakeCompressed<'info>>,
+        nonce: u64,
+        index: u32,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        forfeit_rewards: bool,
+    ) -> Result<()> {
+        force_unstake_compressed_processor(
+            ctx,
+            nonce,
+            index,
+            root,
+            data_hash,
+            creator_hash,
+            forfeit_rewards,
+        )?;
+        Ok(())
+    }
+
+    pub fn migrate_stake_info(ctx: Context<MigrateStakeInfo>) -> Result<()> {
+        migrate_stake_info_processor(ctx)?;
+        Ok(())
+    }
 }
@@ -0,0 +1,46 @@
+use anchor_lang::{prelude::*, solana_program::keccak};
+use mpl_bubblegum::state::metaplex_adapter::{Creator, MetadataArgs};
+
+use crate::state::RoleType;
+
+// Leaf the update authority publishes for every NFT in the collection
+pub fn role_leaf(mint: &Pubkey, role: RoleType, rarity: u16) -> [u8; 32] {
+    keccak::hashv(&[mint.as_ref(), &[role as u8], &rarity.to_le_bytes()]).0
+}
+
+// Pairs are hashed in sorted order so proofs carry no left/right flags
+pub fn verify_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed_root = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).0
+        } else {
+            keccak::hashv(&[sibling, &node]).0
+        }
+    });
+    computed_root == root
+}
+
+// Matches the data hash Bubblegum stores in the leaf
+pub fn hash_metadata(metadata: &MetadataArgs) -> Result<[u8; 32]> {
+    let metadata_args_hash = keccak::hashv(&[metadata.try_to_vec()?.as_slice()]);
+    Ok(keccak::hashv(&[
+        &metadata_args_hash.to_bytes(),
+        &metadata.seller_fee_basis_points.to_le_bytes(),
+    ])
+    .to_bytes())
+}
+
+pub fn hash_creators(creators: &[Creator]) -> [u8; 32] {
+    let creator_data = creators
+        .iter()
+        .map(|c| [c.address.as_ref(), &[c.verified as u8], &[c.share]].concat())
+        .collect::<Vec<_>>();
+    keccak::hashv(
+        creator_data
+            .iter()
+            .map(|c| c.as_slice())
+            .collect::<Vec<&[u8]>>()
+            .as_ref(),
+    )
+    .to_bytes()
+}
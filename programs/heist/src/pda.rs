@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+pub const GLOBAL_SEED: &[u8] = b"global";
+pub const PLAYER_SEED: &[u8] = b"player";
+pub const STAKE_INFO_SEED: &[u8] = b"stake_info";
+pub const BLOCKLIST_SEED: &[u8] = b"blocklist";
+pub const PENDING_CHANGE_SEED: &[u8] = b"pending_change";
+pub const ASSET_SEED: &[u8] = b"asset";
+
+// Seeds: ["global"]
+pub fn find_global_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL_SEED], &crate::ID)
+}
+
+// Seeds: ["blocklist"]
+pub fn find_blocklist_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BLOCKLIST_SEED], &crate::ID)
+}
+
+// Seeds: ["player", player]
+pub fn find_player_info_address(player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PLAYER_SEED, player.as_ref()], &crate::ID)
+}
+
+// Seeds: ["stake_info", player, mint], compressed NFTs use the asset id as mint
+pub fn find_player_stake_info_address(player: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[STAKE_INFO_SEED, player.as_ref(), mint.as_ref()],
+        &crate::ID,
+    )
+}
+
+// Seeds: ["pending_change", change_id as little endian]
+pub fn find_pending_change_address(change_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PENDING_CHANGE_SEED, &change_id.to_le_bytes()], &crate::ID)
+}
+
+// Seeds: ["stake_info", player, nft_account], only kept to migrate old stakes
+pub fn find_legacy_player_stake_info_address(
+    player: &Pubkey,
+    nft_account: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[STAKE_INFO_SEED, player.as_ref(), nft_account.as_ref()],
+        &crate::ID,
+    )
+}
+
+// Compressed NFTs have no mint account, the asset id stands in for the mint
+pub fn get_asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[ASSET_SEED, merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &mpl_bubblegum::id(),
+    )
+    .0
+}
@@ -1,7 +1,18 @@
+use crate::{economics::*, merkle::*, pda::*, MigrateStakeInfo, PublishRoleMerkleRoot};
 use crate::{error::ErrorCode, state::Role, InitOrUpdateCollection};
-use crate::{state::*, StakePlayerStakeInfo, UnstakePlayerStakeInfo, UpdateCollectionAuthority};
-use anchor_lang::prelude::*;
-use anchor_spl::token::{approve, revoke, transfer, Approve, Revoke, Transfer};
+use crate::{events::*, AcceptAuthority, CancelAuthorityTransfer, ProposeAuthority};
+use crate::{state::*, StakePlayerStakeInfo, UnstakePlayerStakeInfo};
+use crate::{CancelChange, ExecuteChange, QueueChange, UpdatePermissions, WithdrawTreasury};
+use crate::{CrankStaleStake, StakeCompressedPlayerStakeInfo, UnstakeCompressedPlayerStakeInfo};
+use crate::{EmergencyUnstake, EmergencyUnstakeCompressed, ForceUnstake, ForceUnstakeCompressed};
+use crate::{Pause, PreviewUnstake, UpdateBlocklist};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::set_return_data, program_option::COption},
+};
+use anchor_spl::token::{approve, close_account, revoke, transfer};
+use anchor_spl::token::{Approve, CloseAccount, Revoke, Transfer};
+use mpl_bubblegum::state::{leaf_schema::LeafSchema, metaplex_adapter::MetadataArgs};
 use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
 
 pub fn init_or_update_collection_processor(
@@ -12,21 +23,33 @@ pub fn init_or_update_collection_processor(
     let metadata: Metadata =
         Metadata::from_account_info(&ctx.accounts.nft_metadata.to_account_info())?;
     let collection = metadata.collection.unwrap();
-    if collection.key != ctx.accounts.collection_mint.key() && !collection.verified {
+    if collection.key != ctx.accounts.collection_mint.key() || !collection.verified {
         return err!(ErrorCode::MismatchCollection);
     };
     if metadata.mint != ctx.accounts.user_nft_mint.key() {
         return err!(ErrorCode::InvalidMintAddress);
     }
 
-    let global_state = &mut ctx.accounts.global_state;
+    // Fresh pool accounts have no discriminator yet
+    let mut global_state = if ctx.accounts.global_state.load().is_err() {
+        ctx.accounts.global_state.load_init()?
+    } else {
+        ctx.accounts.global_state.load_mut()?
+    };
 
-    if !global_state.is_initialized {
-        global_state.is_initialized = true;
+    let is_new_pool = !global_state.is_initialized();
+    if is_new_pool {
+        global_state.is_initialized = 1;
         global_state.total_supply = 0;
         global_state.total_player = 0;
         global_state.collection = ctx.accounts.collection_mint.key();
         global_state.update_authority = ctx.accounts.creator.key();
+        global_state.operator = ctx.accounts.creator.key();
+        global_state.treasurer = ctx.accounts.creator.key();
+        global_state.pauser = ctx.accounts.creator.key();
+        global_state.change_delay = DEFAULT_CHANGE_DELAY;
+        global_state.min_rtp_bps = 0;
+        global_state.max_rtp_bps = u32::MAX;
 
         global_state.roles = Role::new();
         global_state.banks = Bank::generate_banks(reward_per_hour);
@@ -34,12 +57,17 @@ pub fn init_or_update_collection_processor(
         return err!(ErrorCode::InvalidUpdateAuthority);
     }
 
+    // Calculate Total Reward Required, a started hour is funded in full
+    let time_to_end = end_date
+        .checked_sub(Clock::get()?.unix_timestamp as u64)
+        .filter(|&seconds| seconds > 0)
+        .ok_or(ErrorCode::EndDateInPast)?;
+    // Nothing accrues past end_date, pulling it in would cut live stakes' rewards
+    if !is_new_pool && end_date < global_state.end_date {
+        return err!(ErrorCode::EndDateShortened);
+    }
     global_state.end_date = end_date;
-
-    // Calculate Total Reward Required
-    let total_reward_per_hour = global_state.banks.len() as u64 * reward_per_hour;
-    let time_to_end = global_state.end_date - Clock::get().unwrap().unix_timestamp as u64;
-    let total_reward_amount: u64 = time_to_end * total_reward_per_hour;
+    let total_reward_amount = season_funding(reward_per_hour, time_to_end);
 
     // Transfer Reward token to Global State
     let cpi_program = ctx.accounts.token_program.to_account_info();
@@ -53,38 +81,241 @@ pub fn init_or_update_collection_processor(
 
     global_state.reward_mint = ctx.accounts.reward_mint.key();
 
+    // Moving end_date commits live stakes to more hours
+    ctx.accounts.global_reward_token_account.reload()?;
+    require_solvent(
+        &global_state,
+        ctx.accounts.global_reward_token_account.amount,
+    )?;
+
+    if is_new_pool {
+        emit!(PoolInitialized {
+            collection: global_state.collection,
+            update_authority: global_state.update_authority,
+            reward_mint: global_state.reward_mint,
+            end_date,
+            reward_per_hour,
+        });
+    } else {
+        emit!(PoolUpdated {
+            update_authority: global_state.update_authority,
+            end_date,
+            reward_deposited: total_reward_amount,
+        });
+    }
+    Ok(())
+}
+
+pub fn propose_authority_processor(ctx: Context<ProposeAuthority>) -> Result<()> {
+    let mut global_state = ctx.accounts.global_state.load_mut()?;
+    global_state.pending_authority = ctx.accounts.new_authority.key();
+
+    emit!(AuthorityTransferProposed {
+        current_authority: global_state.update_authority,
+        pending_authority: global_state.pending_authority,
+    });
+    Ok(())
+}
+
+pub fn accept_authority_processor(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let mut global_state = ctx.accounts.global_state.load_mut()?;
+    let previous_authority = global_state.update_authority;
+    let new_authority = ctx.accounts.new_authority.key();
+    global_state.update_authority = new_authority;
+    global_state.pending_authority = Pubkey::default();
+
+    emit!(AuthorityTransferAccepted {
+        previous_authority,
+        new_authority,
+    });
+
+    // Roles the previous authority kept go with it, delegated ones stay put
+    let (operator, treasurer, pauser) = (
+        global_state.operator,
+        global_state.treasurer,
+        global_state.pauser,
+    );
+    let hand_over = |key: Pubkey| {
+        if key == previous_authority {
+            new_authority
+        } else {
+            key
+        }
+    };
+    global_state.operator = hand_over(operator);
+    global_state.treasurer = hand_over(treasurer);
+    global_state.pauser = hand_over(pauser);
+    if (operator, treasurer, pauser)
+        != (
+            global_state.operator,
+            global_state.treasurer,
+            global_state.pauser,
+        )
+    {
+        emit!(PermissionsUpdated {
+            operator: global_state.operator,
+            treasurer: global_state.treasurer,
+            pauser: global_state.pauser,
+        });
+    }
     Ok(())
 }
 
-pub fn update_collection_authority_processor(
-    ctx: Context<UpdateCollectionAuthority>,
+pub fn cancel_authority_transfer_processor(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+    let mut global_state = ctx.accounts.global_state.load_mut()?;
+    if global_state.pending_authority == Pubkey::default() {
+        return err!(ErrorCode::NoPendingAuthority);
+    }
+    let pending_authority = global_state.pending_authority;
+    global_state.pending_authority = Pubkey::default();
+
+    emit!(AuthorityTransferCancelled {
+        current_authority: global_state.update_authority,
+        pending_authority,
+    });
+    Ok(())
+}
+
+pub fn update_permissions_processor(
+    ctx: Context<UpdatePermissions>,
+    operator: Pubkey,
+    treasurer: Pubkey,
+    pauser: Pubkey,
 ) -> Result<()> {
-    ctx.accounts.global_state.update_authority = *ctx.accounts.new_authority.key;
+    let mut global_state = ctx.accounts.global_state.load_mut()?;
+    global_state.operator = operator;
+    global_state.treasurer = treasurer;
+    global_state.pauser = pauser;
+
+    emit!(PermissionsUpdated {
+        operator,
+        treasurer,
+        pauser,
+    });
+    Ok(())
+}
+
+pub fn withdraw_treasury_processor(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    // Not timelocked, only the surplus over what live stakes can claim leaves
+    let committed_rewards = ctx.accounts.global_state.load()?.committed_rewards();
+    let surplus = ctx
+        .accounts
+        .global_reward_token_account
+        .amount
+        .saturating_sub(committed_rewards);
+    if amount > surplus {
+        return err!(ErrorCode::InsufficientVault);
+    }
+
+    let auth_bump = *ctx.bumps.get("global_state").unwrap();
+    let seeds = &[GLOBAL_SEED, &[auth_bump]];
+    let signer = &[&seeds[..]];
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.global_reward_token_account.to_account_info(),
+        to: ctx.accounts.destination_token_account.to_account_info(),
+        authority: ctx.accounts.global_state.to_account_info(),
+    };
+    let token_transfer_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    transfer(token_transfer_context, amount)?;
+
+    emit!(TreasuryWithdrawn {
+        treasurer: ctx.accounts.treasurer.key(),
+        destination: ctx.accounts.destination_token_account.key(),
+        amount,
+    });
+    Ok(())
+}
+
+pub fn queue_change_processor(
+    ctx: Context<QueueChange>,
+    change_id: u64,
+    change: ConfigChange,
+) -> Result<()> {
+    let mut global_state = ctx.accounts.global_state.load_mut()?;
+    validate_config_change(&change)?;
+    if change_id != global_state.change_nonce {
+        return err!(ErrorCode::InvalidChangeId);
+    }
+    global_state.change_nonce += 1;
+
+    let current_time = Clock::get().unwrap().unix_timestamp as u64;
+    let pending_change = &mut ctx.accounts.pending_change;
+    pending_change.id = change_id;
+    pending_change.change = change;
+    pending_change.queued_at = current_time;
+    pending_change.earliest_execution_time = current_time + global_state.change_delay;
+
+    emit!(ChangeQueued {
+        id: change_id,
+        earliest_execution_time: pending_change.earliest_execution_time,
+    });
+    Ok(())
+}
+
+pub fn execute_change_processor(ctx: Context<ExecuteChange>) -> Result<()> {
+    let pending_change = &ctx.accounts.pending_change;
+    let current_time = Clock::get().unwrap().unix_timestamp as u64;
+    if current_time < pending_change.earliest_execution_time {
+        return err!(ErrorCode::ChangeTimelocked);
+    }
+
+    // Bounds are read now, a bounds change has to execute before the tables it admits
+    let mut global_state = ctx.accounts.global_state.load_mut()?;
+    check_rtp_bounds(&global_state, &pending_change.change)?;
+    apply_config_change(&mut global_state, &pending_change.change);
+    // Raising a rate or multiplier raises what live stakes can claim
+    require_solvent(
+        &global_state,
+        ctx.accounts.global_reward_token_account.amount,
+    )?;
+
+    emit!(ChangeExecuted {
+        id: pending_change.id,
+    });
+    Ok(())
+}
+
+pub fn cancel_change_processor(ctx: Context<CancelChange>) -> Result<()> {
+    // Closing the pending change is all there is to it
+    emit!(ChangeCancelled {
+        id: ctx.accounts.pending_change.id,
+    });
+    Ok(())
+}
+
+pub fn publish_role_merkle_root_processor(
+    ctx: Context<PublishRoleMerkleRoot>,
+    role_merkle_root: [u8; 32],
+) -> Result<()> {
+    // Not timelocked, role and rarity are copied into a stake when it is made
+    // so a new root only applies to stakes made after it
+    ctx.accounts.global_state.load_mut()?.role_merkle_root = role_merkle_root;
+
+    emit!(RoleMerkleRootPublished { role_merkle_root });
     Ok(())
 }
 
 pub fn stake_player_stake_info_processor(
     ctx: Context<StakePlayerStakeInfo>,
     bank_tier_risk: BankTierRisk,
+    role_proof: RoleProof,
 ) -> Result<()> {
     // Verify if player owns the correct NFT
     let metadata: Metadata =
         Metadata::from_account_info(&ctx.accounts.nft_metadata.to_account_info())?;
     let collection = metadata.collection.unwrap();
-    if collection.key != ctx.accounts.collection_mint.key() && !collection.verified {
+    if collection.key != ctx.accounts.collection_mint.key() || !collection.verified {
         return err!(ErrorCode::MismatchCollection);
     };
     if metadata.mint != ctx.accounts.user_nft_mint.key() {
         return err!(ErrorCode::InvalidMintAddress);
     }
-
-    if !ctx.accounts.player_info.is_initialized {
-        ctx.accounts.player_info.is_initialized = true;
-        ctx.accounts.player_info.point_balance = 0;
-        ctx.accounts.player_info.active_staked = 0;
-
-        ctx.accounts.global_state.total_player += 1;
-    }
+    verify_role_proof(
+        &ctx.accounts.global_state.load()?,
+        &ctx.accounts.user_nft_mint.key(),
+        &role_proof,
+    )?;
 
     // Proceed to Delegate
     let cpi_program = ctx.accounts.token_program.to_account_info();
@@ -96,22 +327,22 @@ pub fn stake_player_stake_info_processor(
     let delegate_token_context = CpiContext::new(cpi_program, cpi_accounts);
     approve(delegate_token_context, 1)?;
 
-    // Update player_into
-    ctx.accounts.player_info.active_staked += 1;
-
-    // Update player_stake_into
-    ctx.accounts.player_stake_info.owner = ctx.accounts.player.key();
-    ctx.accounts.player_stake_info.mint = ctx.accounts.user_nft_mint.key();
-    ctx.accounts.player_stake_info.bank = bank_tier_risk;
-    ctx.accounts.player_stake_info.stake_start_time = Clock::get().unwrap().unix_timestamp as u64;
-
-    // Update global_state
-    match bank_tier_risk {
-        BankTierRisk::Low => ctx.accounts.global_state.banks[0].total_staked += 1,
-        BankTierRisk::Mid => ctx.accounts.global_state.banks[1].total_staked += 1,
-        BankTierRisk::High => ctx.accounts.global_state.banks[2].total_staked += 1,
-    }
+    let mut global_state = ctx.accounts.global_state.load_mut()?;
+    record_stake(
+        &mut global_state,
+        &mut ctx.accounts.player_info,
+        &mut ctx.accounts.player_stake_info,
+        ctx.accounts.player.key(),
+        ctx.accounts.user_nft_mint.key(),
+        bank_tier_risk,
+        &role_proof,
+    )?;
+    require_solvent(
+        &global_state,
+        ctx.accounts.global_reward_token_account.amount,
+    )?;
 
+    emit_staked(&ctx.accounts.player_stake_info);
     Ok(())
 }
 
@@ -120,7 +351,7 @@ pub fn unstake_player_stake_info_processor(ctx: Context<UnstakePlayerStakeInfo>)
     let metadata: Metadata =
         Metadata::from_account_info(&ctx.accounts.nft_metadata.to_account_info())?;
     let collection = metadata.collection.unwrap();
-    if collection.key != ctx.accounts.collection_mint.key() && !collection.verified {
+    if collection.key != ctx.accounts.collection_mint.key() || !collection.verified {
         return err!(ErrorCode::MismatchCollection);
     };
     if metadata.mint != ctx.accounts.nft_mint.key() {
@@ -130,7 +361,7 @@ pub fn unstake_player_stake_info_processor(ctx: Context<UnstakePlayerStakeInfo>)
     // Proceed to revoke delegate
     let auth_bump = *ctx.bumps.get("player_stake_info").unwrap();
     let seeds = &[
-        b"stake_info".as_ref(),
+        STAKE_INFO_SEED,
         &ctx.accounts.player.key().to_bytes(),
         &ctx.accounts.nft_mint.key().to_bytes(),
         &[auth_bump],
@@ -144,65 +375,819 @@ pub fn unstake_player_stake_info_processor(ctx: Context<UnstakePlayerStakeInfo>)
     let revoke_token_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
     revoke(revoke_token_context)?;
 
-    // CALCULATE REWARDS POINTS AND ADD TO POINT BALANCE
-    let player_info = &mut ctx.accounts.player_info;
-    let player_stake_info = &mut ctx.accounts.player_stake_info;
+    settle_stake(
+        &ctx.accounts.global_state,
+        *ctx.bumps.get("global_state").unwrap(),
+        &mut ctx.accounts.player_info,
+        &ctx.accounts.player_stake_info,
+        ctx.accounts.global_reward_token_account.to_account_info(),
+        ctx.accounts.player_reward_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    Ok(())
+}
+
+pub fn stake_compressed_player_stake_info_processor<'info>(
+    ctx: Context<'_, '_, '_, 'info, StakeCompressedPlayerStakeInfo<'info>>,
+    nonce: u64,
+    index: u32,
+    root: [u8; 32],
+    bank_tier_risk: BankTierRisk,
+    metadata: MetadataArgs,
+    role_proof: RoleProof,
+) -> Result<()> {
+    // Verify the leaf metadata belongs to the verified collection
+    let collection = metadata
+        .collection
+        .as_ref()
+        .ok_or(ErrorCode::MismatchCollection)?;
+    if !collection.verified || collection.key != ctx.accounts.global_state.load()?.collection {
+        return err!(ErrorCode::MismatchCollection);
+    }
+
+    // Verify player owns the leaf, the hashes tie the metadata above to the tree
+    let asset_id = get_asset_id(&ctx.accounts.merkle_tree.key(), nonce);
+    verify_role_proof(&ctx.accounts.global_state.load()?, &asset_id, &role_proof)?;
+    let data_hash = hash_metadata(&metadata)?;
+    let creator_hash = hash_creators(&metadata.creators);
+    let leaf = LeafSchema::new_v0(
+        asset_id,
+        ctx.accounts.player.key(),
+        ctx.accounts.player.key(),
+        nonce,
+        data_hash,
+        creator_hash,
+    );
+    let cpi_program = ctx.accounts.compression_program.to_account_info();
+    let cpi_accounts = spl_account_compression::cpi::accounts::VerifyLeaf {
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+    };
+    let verify_leaf_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    spl_account_compression::cpi::verify_leaf(verify_leaf_context, root, leaf.to_node(), index)?;
+
+    // Proceed to Delegate
+    let cpi_program = ctx.accounts.bubblegum_program.to_account_info();
+    let cpi_accounts = mpl_bubblegum::cpi::accounts::Delegate {
+        tree_authority: ctx.accounts.tree_authority.to_account_info(),
+        leaf_owner: ctx.accounts.player.to_account_info(),
+        previous_leaf_delegate: ctx.accounts.player.to_account_info(),
+        new_leaf_delegate: ctx.accounts.player_stake_info.to_account_info(),
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+        log_wrapper: ctx.accounts.log_wrapper.to_account_info(),
+        compression_program: ctx.accounts.compression_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    };
+    let delegate_leaf_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    mpl_bubblegum::cpi::delegate(
+        delegate_leaf_context,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+    )?;
+
+    let mut global_state = ctx.accounts.global_state.load_mut()?;
+    record_stake(
+        &mut global_state,
+        &mut ctx.accounts.player_info,
+        &mut ctx.accounts.player_stake_info,
+        ctx.accounts.player.key(),
+        asset_id,
+        bank_tier_risk,
+        &role_proof,
+    )?;
+    require_solvent(
+        &global_state,
+        ctx.accounts.global_reward_token_account.amount,
+    )?;
+    ctx.accounts.player_stake_info.is_compressed = true;
+
+    emit_staked(&ctx.accounts.player_stake_info);
+    Ok(())
+}
+
+pub fn unstake_compressed_player_stake_info_processor<'info>(
+    ctx: Context<'_, '_, '_, 'info, UnstakeCompressedPlayerStakeInfo<'info>>,
+    nonce: u64,
+    index: u32,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+) -> Result<()> {
+    // Hand the leaf delegate back to the player, Bubblegum verifies the proof
+    // against a leaf still owned by the player and delegated to this stake
+    let cpi_program = ctx.accounts.bubblegum_program.to_account_info();
+    let cpi_accounts = mpl_bubblegum::cpi::accounts::Delegate {
+        tree_authority: ctx.accounts.tree_authority.to_account_info(),
+        leaf_owner: ctx.accounts.player.to_account_info(),
+        previous_leaf_delegate: ctx.accounts.player_stake_info.to_account_info(),
+        new_leaf_delegate: ctx.accounts.player.to_account_info(),
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+        log_wrapper: ctx.accounts.log_wrapper.to_account_info(),
+        compression_program: ctx.accounts.compression_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    };
+    let delegate_leaf_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    mpl_bubblegum::cpi::delegate(
+        delegate_leaf_context,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+    )?;
+
+    settle_stake(
+        &ctx.accounts.global_state,
+        *ctx.bumps.get("global_state").unwrap(),
+        &mut ctx.accounts.player_info,
+        &ctx.accounts.player_stake_info,
+        ctx.accounts.global_reward_token_account.to_account_info(),
+        ctx.accounts.player_reward_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    Ok(())
+}
 
-    let bank: Bank;
-    match player_stake_info.bank {
-        BankTierRisk::Low => bank = ctx.accounts.global_state.banks[0].clone(),
-        BankTierRisk::Mid => bank = ctx.accounts.global_state.banks[1].clone(),
-        BankTierRisk::High => bank = ctx.accounts.global_state.banks[2].clone(),
+pub fn crank_stale_stake_processor(ctx: Context<CrankStaleStake>) -> Result<()> {
+    let nft_holder_account = &ctx.accounts.nft_holder_account;
+    let player_stake_info = &ctx.accounts.player_stake_info;
+
+    // NFTs have a supply of one, the account holding it shows where it went
+    let is_stale = if ctx.accounts.nft_mint.supply == 0 {
+        true
+    } else {
+        if nft_holder_account.amount != 1 {
+            return err!(ErrorCode::InvalidStaleProof);
+        }
+        nft_holder_account.owner != player_stake_info.owner
+            || nft_holder_account.delegate != COption::Some(player_stake_info.key())
+            || nft_holder_account.delegated_amount == 0
+    };
+    if !is_stale {
+        return err!(ErrorCode::StakeNotStale);
     }
 
+    // Rewards are forfeited, only the stake bookkeeping is undone
+    release_stake(
+        &mut ctx.accounts.global_state.load_mut()?,
+        &mut ctx.accounts.player_info,
+        player_stake_info,
+    )?;
+
+    emit!(StaleStakeCranked {
+        owner: player_stake_info.owner,
+        mint: player_stake_info.mint,
+        cranker: ctx.accounts.cranker.key(),
+    });
+    Ok(())
+}
+
+pub fn pause_processor(ctx: Context<Pause>) -> Result<()> {
+    ctx.accounts.global_state.load_mut()?.set_paused(true);
+
+    emit!(ProgramPaused {
+        pauser: ctx.accounts.pauser.key(),
+    });
+    Ok(())
+}
+
+pub fn unpause_processor(ctx: Context<Pause>) -> Result<()> {
+    ctx.accounts.global_state.load_mut()?.set_paused(false);
+
+    emit!(ProgramUnpaused {
+        pauser: ctx.accounts.pauser.key(),
+    });
+    Ok(())
+}
+
+pub fn emergency_unstake_processor(ctx: Context<EmergencyUnstake>) -> Result<()> {
+    // Hand the NFT back without rolling or paying out
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_accounts = Revoke {
+        source: ctx.accounts.user_nft_account.to_account_info(),
+        authority: ctx.accounts.player.to_account_info(),
+    };
+    let revoke_token_context = CpiContext::new(cpi_program, cpi_accounts);
+    revoke(revoke_token_context)?;
+
+    release_stake(
+        &mut ctx.accounts.global_state.load_mut()?,
+        &mut ctx.accounts.player_info,
+        &ctx.accounts.player_stake_info,
+    )?;
+
+    Ok(())
+}
+
+pub fn emergency_unstake_compressed_processor<'info>(
+    ctx: Context<'_, '_, '_, 'info, EmergencyUnstakeCompressed<'info>>,
+    nonce: u64,
+    index: u32,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+) -> Result<()> {
+    // Hand the leaf delegate back without rolling or paying out
+    let cpi_program = ctx.accounts.bubblegum_program.to_account_info();
+    let cpi_accounts = mpl_bubblegum::cpi::accounts::Delegate {
+        tree_authority: ctx.accounts.tree_authority.to_account_info(),
+        leaf_owner: ctx.accounts.player.to_account_info(),
+        previous_leaf_delegate: ctx.accounts.player_stake_info.to_account_info(),
+        new_leaf_delegate: ctx.accounts.player.to_account_info(),
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+        log_wrapper: ctx.accounts.log_wrapper.to_account_info(),
+        compression_program: ctx.accounts.compression_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    };
+    let delegate_leaf_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    mpl_bubblegum::cpi::delegate(
+        delegate_leaf_context,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+    )?;
+
+    release_stake(
+        &mut ctx.accounts.global_state.load_mut()?,
+        &mut ctx.accounts.player_info,
+        &ctx.accounts.player_stake_info,
+    )?;
+
+    Ok(())
+}
+
+pub fn preview_unstake_processor(ctx: Context<PreviewUnstake>) -> Result<()> {
+    let global_state = ctx.accounts.global_state.load()?;
+    let player_stake_info = &ctx.accounts.player_stake_info;
+    let bank = global_state.bank(player_stake_info.bank);
+    let role = global_state.role(player_stake_info.role);
+
     let current_time = Clock::get().unwrap().unix_timestamp as u64;
-    let base_reward_amount =
-        (current_time - player_stake_info.stake_start_time) / 3600 * bank.reward_per_hour;
+    let base_reward = accrued_reward(
+        bank.reward_per_hour,
+        role.reward_boost_bps,
+        player_stake_info.rarity,
+        elapsed_since(
+            player_stake_info.stake_start_time,
+            current_time.min(global_state.end_date),
+        ),
+    );
+
+    // Odds as the player's role sees them
+    let odds = shifted_odds(bank.outcomes().iter().map(|o| o.odds), role.odds_shift);
+    let outcomes: Vec<OutcomePreview> = bank
+        .outcomes()
+        .iter()
+        .zip(odds)
+        .map(|(outcome, odds)| OutcomePreview {
+            odds,
+            payout_multiplier: outcome.payout_multiplier,
+            negative_outcome: outcome.negative_outcome(),
+            reward: payout(base_reward, outcome.payout_multiplier),
+        })
+        .collect();
+    let expected_reward = (outcomes
+        .iter()
+        .map(|o| u128::from(o.reward) * u128::from(o.odds))
+        .sum::<u128>()
+        / u128::from(TOTAL_ODDS)) as u64;
 
-    let pseudo_random_number = generate_random_number(&ctx.accounts.player.key()); // Between 1 - 100_000
+    // Read through simulateTransaction, nothing is written
+    set_return_data(
+        &UnstakePreview {
+            base_reward,
+            expected_reward,
+            outcomes,
+        }
+        .try_to_vec()?,
+    );
+    Ok(())
+}
+
+pub fn block_player_processor(ctx: Context<UpdateBlocklist>, player: Pubkey) -> Result<()> {
+    let blocklist = &mut ctx.accounts.blocklist;
+    if !blocklist.wallets.contains(&player) {
+        if blocklist.wallets.len() >= MAX_BLOCKED_WALLETS {
+            return err!(ErrorCode::BlocklistFull);
+        }
+        blocklist.wallets.push(player);
+    }
+
+    emit!(PlayerBlocked { player });
+    Ok(())
+}
 
-    let multiplier = bank
-        .bank_outcomes
+pub fn unblock_player_processor(ctx: Context<UpdateBlocklist>, player: Pubkey) -> Result<()> {
+    let blocklist = &mut ctx.accounts.blocklist;
+    let index = blocklist
+        .wallets
         .iter()
-        .find(|&&bank_tier| u64::from(bank_tier.odds) > pseudo_random_number)
-        .unwrap();
+        .position(|wallet| *wallet == player)
+        .ok_or(ErrorCode::PlayerNotBlocked)?;
+    blocklist.wallets.swap_remove(index);
+
+    emit!(PlayerUnblocked { player });
+    Ok(())
+}
+
+pub fn force_unstake_processor(ctx: Context<ForceUnstake>, forfeit_rewards: bool) -> Result<()> {
+    let player_stake_info = &ctx.accounts.player_stake_info;
+    let user_nft_account = &ctx.accounts.user_nft_account;
+    let stake_bump = *ctx.bumps.get("player_stake_info").unwrap();
+    let seeds = &[
+        STAKE_INFO_SEED,
+        player_stake_info.owner.as_ref(),
+        player_stake_info.mint.as_ref(),
+        &[stake_bump],
+    ];
+    let signer = &[&seeds[..]];
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    // A moved NFT left nothing behind for the stake to move
+    if user_nft_account.amount == 1
+        && user_nft_account.delegate == COption::Some(player_stake_info.key())
+    {
+        let cpi_accounts = Transfer {
+            from: user_nft_account.to_account_info(),
+            to: ctx.accounts.escrow_nft_account.to_account_info(),
+            authority: player_stake_info.to_account_info(),
+        };
+        transfer(
+            CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer),
+            1,
+        )?;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.escrow_nft_account.to_account_info(),
+            to: user_nft_account.to_account_info(),
+            authority: player_stake_info.to_account_info(),
+        };
+        transfer(
+            CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer),
+            1,
+        )?;
+    }
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.escrow_nft_account.to_account_info(),
+        destination: ctx.accounts.update_authority.to_account_info(),
+        authority: player_stake_info.to_account_info(),
+    };
+    close_account(CpiContext::new_with_signer(
+        token_program,
+        cpi_accounts,
+        signer,
+    ))?;
+
+    if forfeit_rewards {
+        release_stake(
+            &mut ctx.accounts.global_state.load_mut()?,
+            &mut ctx.accounts.player_info,
+            &ctx.accounts.player_stake_info,
+        )?;
+    } else {
+        settle_stake(
+            &ctx.accounts.global_state,
+            *ctx.bumps.get("global_state").unwrap(),
+            &mut ctx.accounts.player_info,
+            &ctx.accounts.player_stake_info,
+            ctx.accounts.global_reward_token_account.to_account_info(),
+            ctx.accounts.player_reward_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
+
+    emit!(StakeForceUnstaked {
+        owner: ctx.accounts.player_stake_info.owner,
+        mint: ctx.accounts.player_stake_info.mint,
+        forfeit_rewards,
+    });
+    Ok(())
+}
+
+pub fn force_unstake_compressed_processor<'info>(
+    ctx: Context<'_, '_, '_, 'info, ForceUnstakeCompressed<'info>>,
+    nonce: u64,
+    index: u32,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    forfeit_rewards: bool,
+) -> Result<()> {
+    // Bubblegum only lets the owner delegate, as the leaf delegate the stake
+    // transfers the leaf to its owner which resets the delegate to the owner
+    let owner = ctx.accounts.owner.key();
+    let stake_bump = *ctx.bumps.get("player_stake_info").unwrap();
+    let seeds = &[
+        STAKE_INFO_SEED,
+        owner.as_ref(),
+        ctx.accounts.player_stake_info.mint.as_ref(),
+        &[stake_bump],
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_program = ctx.accounts.bubblegum_program.to_account_info();
+    let cpi_accounts = mpl_bubblegum::cpi::accounts::Transfer {
+        tree_authority: ctx.accounts.tree_authority.to_account_info(),
+        leaf_owner: ctx.accounts.owner.to_account_info(),
+        leaf_delegate: ctx.accounts.player_stake_info.to_account_info(),
+        new_leaf_owner: ctx.accounts.owner.to_account_info(),
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+        log_wrapper: ctx.accounts.log_wrapper.to_account_info(),
+        compression_program: ctx.accounts.compression_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    };
+    let transfer_leaf_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    mpl_bubblegum::cpi::transfer(
+        transfer_leaf_context,
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index,
+    )?;
+
+    if forfeit_rewards {
+        release_stake(
+            &mut ctx.accounts.global_state.load_mut()?,
+            &mut ctx.accounts.player_info,
+            &ctx.accounts.player_stake_info,
+        )?;
+    } else {
+        settle_stake(
+            &ctx.accounts.global_state,
+            *ctx.bumps.get("global_state").unwrap(),
+            &mut ctx.accounts.player_info,
+            &ctx.accounts.player_stake_info,
+            ctx.accounts.global_reward_token_account.to_account_info(),
+            ctx.accounts.player_reward_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
+
+    emit!(StakeForceUnstaked {
+        owner,
+        mint: ctx.accounts.player_stake_info.mint,
+        forfeit_rewards,
+    });
+    Ok(())
+}
+
+pub fn migrate_stake_info_processor(ctx: Context<MigrateStakeInfo>) -> Result<()> {
+    // Move the delegate over to the canonical stake address
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_accounts = Approve {
+        to: ctx.accounts.user_nft_account.to_account_info(),
+        delegate: ctx.accounts.player_stake_info.to_account_info(),
+        authority: ctx.accounts.player.to_account_info(),
+    };
+    let delegate_token_context = CpiContext::new(cpi_program, cpi_accounts);
+    approve(delegate_token_context, 1)?;
+
+    // Stake keeps its start time so accrued rewards carry over
+    let legacy_player_stake_info = &ctx.accounts.legacy_player_stake_info;
+    let player_stake_info = &mut ctx.accounts.player_stake_info;
+    player_stake_info.owner = legacy_player_stake_info.owner;
+    player_stake_info.mint = legacy_player_stake_info.mint;
+    player_stake_info.bank = legacy_player_stake_info.bank;
+    player_stake_info.stake_start_time = legacy_player_stake_info.stake_start_time;
+    player_stake_info.role = legacy_player_stake_info.role;
+    player_stake_info.is_compressed = false;
+
+    // Legacy stakes could start after the season, nothing accrues either way
+    let mut global_state = ctx.accounts.global_state.load_mut()?;
+    player_stake_info.stake_start_time = player_stake_info
+        .stake_start_time
+        .min(global_state.end_date);
+
+    // Stakes from before rarity weights earn the base rate, in a bank that
+    // still takes their role
+    player_stake_info.rarity = BASE_RARITY_WEIGHT;
+    let bank = global_state.bank_mut(player_stake_info.bank);
+    if !bank.allows(player_stake_info.role) {
+        return err!(ErrorCode::RoleNotAllowedInBank);
+    }
+    bank.add_weight(
+        player_stake_info.role,
+        BASE_RARITY_WEIGHT,
+        player_stake_info.stake_start_time,
+    );
+    require_solvent(
+        &global_state,
+        ctx.accounts.global_reward_token_account.amount,
+    )?;
+
+    emit!(StakeMigrated {
+        owner: player_stake_info.owner,
+        mint: player_stake_info.mint,
+        legacy_stake_info: legacy_player_stake_info.key(),
+    });
+    Ok(())
+}
+
+fn record_stake(
+    global_state: &mut Global,
+    player_info: &mut PlayerInfo,
+    player_stake_info: &mut PlayerStakeInfo,
+    owner: Pubkey,
+    mint: Pubkey,
+    bank_tier_risk: BankTierRisk,
+    role_proof: &RoleProof,
+) -> Result<()> {
+    let current_time = Clock::get().unwrap().unix_timestamp as u64;
+    if player_info.jail_end_time > current_time {
+        return err!(ErrorCode::PlayerJailed);
+    }
+    // Nothing accrues past end_date, committed rewards rely on stakes starting before it
+    if current_time >= global_state.end_date {
+        return err!(ErrorCode::SeasonEnded);
+    }
+
+    if !player_info.is_initialized {
+        player_info.is_initialized = true;
+        player_info.point_balance = 0;
+        player_info.active_staked = 0;
+        player_info.jail_end_time = 0;
+    }
+
+    // Update player_into, total_player counts players with a live stake
+    if player_info.active_staked == 0 {
+        global_state.total_player += 1;
+    }
+    player_info.active_staked += 1;
+
+    // Update player_stake_into
+    player_stake_info.owner = owner;
+    player_stake_info.mint = mint;
+    player_stake_info.bank = bank_tier_risk;
+    player_stake_info.stake_start_time = current_time;
+    player_stake_info.role = role_proof.role;
+    player_stake_info.rarity = role_proof.rarity;
+
+    // Update global_state
+    let bank = global_state.bank_mut(bank_tier_risk);
+    if !bank.allows(role_proof.role) {
+        return err!(ErrorCode::RoleNotAllowedInBank);
+    }
+    bank.total_staked += 1;
+    bank.add_weight(role_proof.role, role_proof.rarity, current_time);
+
+    Ok(())
+}
+
+// Checks that hold whatever else executes first, run at queue
+fn validate_config_change(change: &ConfigChange) -> Result<()> {
+    match change {
+        ConfigChange::UpdateBank { bank_outcomes, .. } => {
+            if bank_outcomes.len() > MAX_BANK_OUTCOMES {
+                return err!(ErrorCode::TooManyBankOutcomes);
+            }
+            // Odds shifts move rolls towards the first outcomes, those have to be the good ones
+            if bank_outcomes
+                .windows(2)
+                .any(|pair| pair[0].is_negative && !pair[1].is_negative)
+            {
+                return err!(ErrorCode::InvalidOutcomeOrder);
+            }
+            let total_odds: u64 = bank_outcomes.iter().map(|o| u64::from(o.odds)).sum();
+            if total_odds != u64::from(TOTAL_ODDS) {
+                return err!(ErrorCode::InvalidBankOdds);
+            }
+        }
+        ConfigChange::UpdateRole {
+            odds_shift,
+            jail_time_reduction,
+            ..
+        } => {
+            if *odds_shift > TOTAL_ODDS || *jail_time_reduction > ARREST_JAIL_TIME {
+                return err!(ErrorCode::InvalidRoleModifier);
+            }
+        }
+        ConfigChange::UpdateRtpBounds {
+            min_rtp_bps,
+            max_rtp_bps,
+        } => {
+            if min_rtp_bps > max_rtp_bps {
+                return err!(ErrorCode::InvalidRtpBounds);
+            }
+        }
+        ConfigChange::UpdateBankRoles { .. } | ConfigChange::UpdateChangeDelay { .. } => {}
+    }
+    Ok(())
+}
+
+// Only at execute, against the bounds in force when the table takes effect
+fn check_rtp_bounds(global_state: &Global, change: &ConfigChange) -> Result<()> {
+    if let ConfigChange::UpdateBank { bank_outcomes, .. } = change {
+        let rtp_bps = TableStats::from_args(bank_outcomes).rtp_bps();
+        if rtp_bps < u64::from(global_state.min_rtp_bps)
+            || rtp_bps > u64::from(global_state.max_rtp_bps)
+        {
+            return err!(ErrorCode::RtpOutOfBounds);
+        }
+    }
+    Ok(())
+}
+
+fn apply_config_change(global_state: &mut Global, change: &ConfigChange) {
+    match change {
+        ConfigChange::UpdateBank {
+            bank_tier_risk,
+            reward_per_hour,
+            bank_outcomes,
+        } => {
+            let bank_outcomes: Vec<BankOutcome> = bank_outcomes.iter().map(|&o| o.into()).collect();
+            let bank = global_state.bank_mut(*bank_tier_risk);
+            bank.reward_per_hour = *reward_per_hour;
+            bank.set_outcomes(&bank_outcomes);
+        }
+        ConfigChange::UpdateBankRoles {
+            bank_tier_risk,
+            allowed_roles,
+        } => {
+            global_state
+                .bank_mut(*bank_tier_risk)
+                .set_allowed_roles(allowed_roles);
+        }
+        ConfigChange::UpdateRole {
+            role_type,
+            reward_boost_bps,
+            odds_shift,
+            jail_time_reduction,
+        } => {
+            let role = global_state.role_mut(*role_type);
+            role.reward_boost_bps = *reward_boost_bps;
+            role.odds_shift = *odds_shift;
+            role.jail_time_reduction = *jail_time_reduction;
+        }
+        ConfigChange::UpdateChangeDelay { change_delay } => {
+            global_state.change_delay = *change_delay;
+        }
+        ConfigChange::UpdateRtpBounds {
+            min_rtp_bps,
+            max_rtp_bps,
+        } => {
+            global_state.min_rtp_bps = *min_rtp_bps;
+            global_state.max_rtp_bps = *max_rtp_bps;
+        }
+    }
+}
+
+// Funding only has to cover stakes as they come in, each stake, rate change
+// or move of end_date is admitted while the vault covers every live stake
+fn require_solvent(global_state: &Global, vault_balance: u64) -> Result<()> {
+    if vault_balance < global_state.committed_rewards() {
+        return err!(ErrorCode::InsufficientVault);
+    }
+    Ok(())
+}
+
+fn verify_role_proof(global_state: &Global, mint: &Pubkey, role_proof: &RoleProof) -> Result<()> {
+    let leaf = role_leaf(mint, role_proof.role, role_proof.rarity);
+    if !verify_proof(&role_proof.proof, global_state.role_merkle_root, leaf) {
+        return err!(ErrorCode::InvalidRoleProof);
+    }
+    Ok(())
+}
+
+fn settle_stake<'info>(
+    global_state: &AccountLoader<'info, Global>,
+    global_bump: u8,
+    player_info: &mut PlayerInfo,
+    player_stake_info: &PlayerStakeInfo,
+    global_reward_token_account: AccountInfo<'info>,
+    player_reward_token_account: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    #[cfg(feature = "bench")]
+    anchor_lang::solana_program::log::sol_log_compute_units();
+
+    // CALCULATE REWARDS POINTS AND ADD TO POINT BALANCE
+    let reward_amount = {
+        // Only the staked bank is read, global is released before the CPI below
+        let mut global = global_state.load_mut()?;
+        let bank = global.bank(player_stake_info.bank);
+        let role = global.role(player_stake_info.role);
+
+        // Rewards stop accruing at end_date
+        let current_time = Clock::get().unwrap().unix_timestamp as u64;
+        let base_reward_amount = accrued_reward(
+            bank.reward_per_hour,
+            role.reward_boost_bps,
+            player_stake_info.rarity,
+            elapsed_since(
+                player_stake_info.stake_start_time,
+                current_time.min(global.end_date),
+            ),
+        );
+
+        let pseudo_random_number = shifted_roll(
+            roll(current_time, &player_stake_info.owner),
+            role.odds_shift,
+        );
+        let outcome_index =
+            select_outcome(bank.outcomes().iter().map(|o| o.odds), pseudo_random_number).unwrap();
+        let multiplier = &bank.outcomes()[outcome_index];
+
+        let reward_amount = payout(base_reward_amount, multiplier.payout_multiplier);
+
+        emit!(OutcomeRolled {
+            owner: player_stake_info.owner,
+            mint: player_stake_info.mint,
+            bank: player_stake_info.bank,
+            roll: pseudo_random_number,
+            outcome_index: outcome_index as u8,
+            payout_multiplier: multiplier.payout_multiplier,
+            reward_amount,
+        });
+
+        let owner = player_stake_info.owner;
+        let mint = player_stake_info.mint;
+        match multiplier.negative_outcome() {
+            NegativeOutcome::None => {}
+            NegativeOutcome::Fumbled => emit!(StakeFumbled { owner, mint }),
+            NegativeOutcome::Confiscation => emit!(StakeConfiscated { owner, mint }),
+            NegativeOutcome::Arrested => {
+                player_info.jail_end_time =
+                    current_time + ARREST_JAIL_TIME.saturating_sub(role.jail_time_reduction);
+                emit!(PlayerArrested {
+                    owner,
+                    mint,
+                    jail_end_time: player_info.jail_end_time,
+                });
+            }
+            NegativeOutcome::Rekt => emit!(StakeRekt { owner, mint }),
+        }
 
-    let reward_amount = base_reward_amount * u64::from(multiplier.payout_multiplier);
+        release_stake(&mut global, player_info, player_stake_info)?;
+        reward_amount
+    };
 
     player_info.point_balance += reward_amount;
-    player_info.active_staked -= 1;
 
     // TRANSFER REWARD TOKEN
-    let auth_bump = *ctx.bumps.get("global_state").unwrap();
-    let seeds = &[b"global".as_ref(), &[auth_bump]];
+    let seeds = &[GLOBAL_SEED, &[global_bump]];
     let signer = &[&seeds[..]];
-    let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_accounts = Transfer {
-        from: ctx.accounts.global_reward_token_account.to_account_info(),
-        to: ctx.accounts.player_reward_token_account.to_account_info(),
-        authority: ctx.accounts.global_state.to_account_info(),
+        from: global_reward_token_account,
+        to: player_reward_token_account,
+        authority: global_state.to_account_info(),
     };
-    let token_transfer_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    let token_transfer_context = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
     transfer(token_transfer_context, reward_amount)?;
 
-    // test: closing a non existing account
-    if player_info.active_staked == 0 {
-        ctx.accounts.global_state.total_player -= 1;
-    }
+    #[cfg(feature = "bench")]
+    anchor_lang::solana_program::log::sol_log_compute_units();
 
     Ok(())
 }
 
-fn generate_random_number(pubkey: &Pubkey) -> u64 {
-    const A: u64 = 1664525;
-    const C: u64 = 1013904223;
-    const M: u64 = 1 << 32;
+fn release_stake(
+    global_state: &mut Global,
+    player_info: &mut PlayerInfo,
+    player_stake_info: &PlayerStakeInfo,
+) -> Result<()> {
+    player_info.active_staked -= 1;
 
-    let time = Clock::get().unwrap().unix_timestamp as u64;
+    let bank = global_state.bank_mut(player_stake_info.bank);
+    bank.total_staked -= 1;
+    bank.remove_weight(
+        player_stake_info.role,
+        player_stake_info.rarity,
+        player_stake_info.stake_start_time,
+    );
 
-    let psudo_random_number = time ^ pubkey.as_ref().iter().fold(0u64, |acc, &x| acc + x as u64);
+    if player_info.active_staked == 0 {
+        global_state.total_player = global_state
+            .total_player
+            .checked_sub(1)
+            .ok_or(ErrorCode::PlayerCountUnderflow)?;
+    }
 
-    let value = (A * psudo_random_number + C) % M;
+    emit!(Unstaked {
+        owner: player_stake_info.owner,
+        mint: player_stake_info.mint,
+        bank: player_stake_info.bank,
+    });
+    Ok(())
+}
 
-    (value as f64 / M as f64 * 100_000.0).floor() as u64
+fn emit_staked(player_stake_info: &PlayerStakeInfo) {
+    emit!(Staked {
+        owner: player_stake_info.owner,
+        mint: player_stake_info.mint,
+        bank: player_stake_info.bank,
+        role: player_stake_info.role,
+        rarity: player_stake_info.rarity,
+        is_compressed: player_stake_info.is_compressed,
+        stake_start_time: player_stake_info.stake_start_time,
+    });
 }
@@ -1,24 +1,99 @@
-use anchor_lang::{__private::ZeroCopyAccessor, prelude::*};
+use crate::economics::committed_reward;
+use anchor_lang::{prelude::*, Discriminator};
+use std::io::Write;
 
-#[account]
+pub const MAX_BANKS: usize = 3;
+pub const MAX_BANK_OUTCOMES: usize = 16;
+pub const MAX_ROLES: usize = 2;
+pub const MAX_BLOCKED_WALLETS: usize = 200;
+
+#[account(zero_copy)]
 pub struct Global {
     pub collection: Pubkey,
     pub total_supply: u64,
     pub end_date: u64,
     pub reward_mint: Pubkey,
-    pub banks: Vec<Bank>,
-    pub roles: Vec<Role>,
-    pub is_initialized: bool,
+    pub banks: [Bank; MAX_BANKS],
+    pub roles: [Role; MAX_ROLES],
+    // Zero-copy accounts are Pod, flags and enums are kept as raw bytes
+    pub is_initialized: u8,
     pub update_authority: Pubkey,
     pub total_player: u64,
+    // Root of (mint, role, rarity) leaves, see merkle::role_leaf
+    pub role_merkle_root: [u8; 32],
+    // Set by propose_authority until the new authority accepts
+    pub pending_authority: Pubkey,
+    // Keys the update authority hands out for day to day operations
+    pub operator: Pubkey,
+    pub treasurer: Pubkey,
+    pub pauser: Pubkey,
+    // Id the next queued config change has to use
+    pub change_nonce: u64,
+    // Seconds a queued config change waits before it can execute
+    pub change_delay: u64,
+    // Blocks stakes and settlements, emergency_unstake and forfeiting
+    // force unstakes still work
+    pub is_paused: u8,
+    // Advertised return-to-player range every published bank table stays in
+    pub min_rtp_bps: u32,
+    pub max_rtp_bps: u32,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, ZeroCopyAccessor)]
+#[zero_copy]
 pub struct Bank {
-    pub bank_tier: BankTierRisk,
+    pub bank_tier: u8,
     pub reward_per_hour: u64,
     pub total_staked: u64,
-    pub bank_outcomes: Vec<BankOutcome>,
+    // Rarity weights staked in this bank, per RoleType
+    pub role_weights: [u64; MAX_ROLES],
+    // Rarity weighted stake start times, per RoleType. Together with role_weights
+    // they price what every stake can earn up to end_date, see committed_rewards
+    pub role_weighted_starts: [u128; MAX_ROLES],
+    // Bit per RoleType allowed to stake in this bank
+    pub allowed_roles: u8,
+    pub outcome_count: u8,
+    pub bank_outcomes: [BankOutcome; MAX_BANK_OUTCOMES],
+}
+
+#[account]
+pub struct Blocklist {
+    pub wallets: Vec<Pubkey>,
+}
+
+#[account]
+pub struct PendingChange {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub queued_at: u64,
+    pub earliest_execution_time: u64,
+}
+
+// Config updates that can move active stakers' odds or rewards. Role roots and
+// treasury withdrawals skip the queue, neither can touch a live stake
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ConfigChange {
+    UpdateBank {
+        bank_tier_risk: BankTierRisk,
+        reward_per_hour: u64,
+        bank_outcomes: Vec<BankOutcomeArgs>,
+    },
+    UpdateBankRoles {
+        bank_tier_risk: BankTierRisk,
+        allowed_roles: Vec<RoleType>,
+    },
+    UpdateRole {
+        role_type: RoleType,
+        reward_boost_bps: u16,
+        odds_shift: u32,
+        jail_time_reduction: u64,
+    },
+    UpdateChangeDelay {
+        change_delay: u64,
+    },
+    UpdateRtpBounds {
+        min_rtp_bps: u32,
+        max_rtp_bps: u32,
+    },
 }
 
 #[account]
@@ -26,6 +101,7 @@ pub struct PlayerInfo {
     pub is_initialized: bool,
     pub point_balance: u64,
     pub active_staked: u16,
+    pub jail_end_time: u64,
 }
 
 #[account]
@@ -34,42 +110,223 @@ pub struct PlayerStakeInfo {
     pub mint: Pubkey,
     pub bank: BankTierRisk,
     pub stake_start_time: u64,
-    pub role: Role,
+    pub role: RoleType,
+    pub is_compressed: bool,
+    // Reward weight in basis points, BASE_RARITY_WEIGHT earns the bank rate
+    pub rarity: u16,
+}
+
+// Stake accounts from before roles carried rarity, keyed by token account.
+// They share PlayerStakeInfo's discriminator but stop after role
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyPlayerStakeInfo {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub bank: BankTierRisk,
+    pub stake_start_time: u64,
+    pub role: RoleType,
+}
+
+impl Global {
+    pub fn bank(&self, bank_tier_risk: BankTierRisk) -> &Bank {
+        &self.banks[bank_tier_risk as usize]
+    }
+
+    pub fn bank_mut(&mut self, bank_tier_risk: BankTierRisk) -> &mut Bank {
+        &mut self.banks[bank_tier_risk as usize]
+    }
+
+    pub fn role(&self, role_type: RoleType) -> &Role {
+        &self.roles[role_type as usize]
+    }
+
+    pub fn role_mut(&mut self, role_type: RoleType) -> &mut Role {
+        &mut self.roles[role_type as usize]
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.is_initialized != 0
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused != 0
+    }
+
+    pub fn set_paused(&mut self, is_paused: bool) {
+        self.is_paused = is_paused as u8;
+    }
+
+    // Most the live stakes can be paid if each rolled its bank's best outcome
+    // at end_date, the vault has to hold at least this much
+    pub fn committed_rewards(&self) -> u64 {
+        let mut committed = 0u64;
+        for bank in self.banks.iter() {
+            let (role_weights, role_weighted_starts) =
+                (bank.role_weights, bank.role_weighted_starts);
+            for (role, (weight, weighted_start)) in self
+                .roles
+                .iter()
+                .zip(role_weights.into_iter().zip(role_weighted_starts))
+            {
+                committed = committed.saturating_add(committed_reward(
+                    bank.reward_per_hour,
+                    role.reward_boost_bps,
+                    bank.max_multiplier(),
+                    weight,
+                    weighted_start,
+                    self.end_date,
+                ));
+            }
+        }
+        committed
+    }
+
+    // Whether every flag and enum byte holds a value the program writes
+    pub fn is_well_formed(&self) -> bool {
+        self.is_initialized <= 1
+            && self.is_paused <= 1
+            && self.banks.iter().all(Bank::is_well_formed)
+            && self
+                .roles
+                .iter()
+                .all(|role| RoleType::from_u8(role.role_type).is_some())
+    }
 }
 
 impl Bank {
-    pub fn generate_banks(reward_per_hour: u64) -> Vec<Bank> {
-        vec![
-            Bank {
-                bank_tier: BankTierRisk::Low,
+    pub fn new(
+        bank_tier: BankTierRisk,
+        reward_per_hour: u64,
+        bank_outcomes: &[BankOutcome],
+    ) -> Bank {
+        let mut bank = Bank {
+            bank_tier: bank_tier as u8,
+            reward_per_hour,
+            total_staked: 0,
+            role_weights: [0; MAX_ROLES],
+            role_weighted_starts: [0; MAX_ROLES],
+            allowed_roles: 0,
+            outcome_count: 0,
+            bank_outcomes: [BankOutcome::new(false, NegativeOutcome::None, 0, 0);
+                MAX_BANK_OUTCOMES],
+        };
+        bank.set_outcomes(bank_outcomes);
+        bank.set_allowed_roles(&[RoleType::Chimp, RoleType::Gorrila]);
+        bank
+    }
+
+    pub fn generate_banks(reward_per_hour: u64) -> [Bank; MAX_BANKS] {
+        [
+            Bank::new(
+                BankTierRisk::Low,
                 reward_per_hour,
-                total_staked: 0,
-                bank_outcomes: BankOutcome::generate_bank(BankTierRisk::Low),
-            },
-            Bank {
-                bank_tier: BankTierRisk::Mid,
+                &BankOutcome::generate_bank(BankTierRisk::Low),
+            ),
+            Bank::new(
+                BankTierRisk::Mid,
                 reward_per_hour,
-                total_staked: 0,
-                bank_outcomes: BankOutcome::generate_bank(BankTierRisk::Mid),
-            },
-            Bank {
-                bank_tier: BankTierRisk::High,
+                &BankOutcome::generate_bank(BankTierRisk::Mid),
+            ),
+            Bank::new(
+                BankTierRisk::High,
                 reward_per_hour,
-                total_staked: 0,
-                bank_outcomes: BankOutcome::generate_bank(BankTierRisk::High),
-            },
+                &BankOutcome::generate_bank(BankTierRisk::High),
+            ),
         ]
     }
+
+    pub fn bank_tier(&self) -> BankTierRisk {
+        BankTierRisk::from_u8(self.bank_tier).expect("bank tier written by the program")
+    }
+
+    pub fn total_weight(&self) -> u64 {
+        { self.role_weights }.iter().sum()
+    }
+
+    pub fn max_multiplier(&self) -> u8 {
+        self.outcomes()
+            .iter()
+            .map(|outcome| outcome.payout_multiplier)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Packed fields are copied out and back, they can't be borrowed in place
+    pub fn add_weight(&mut self, role: RoleType, rarity: u16, stake_start_time: u64) {
+        let (mut role_weights, mut role_weighted_starts) =
+            (self.role_weights, self.role_weighted_starts);
+        role_weights[role as usize] += u64::from(rarity);
+        role_weighted_starts[role as usize] += u128::from(rarity) * u128::from(stake_start_time);
+        self.role_weights = role_weights;
+        self.role_weighted_starts = role_weighted_starts;
+    }
+
+    pub fn remove_weight(&mut self, role: RoleType, rarity: u16, stake_start_time: u64) {
+        let (mut role_weights, mut role_weighted_starts) =
+            (self.role_weights, self.role_weighted_starts);
+        role_weights[role as usize] -= u64::from(rarity);
+        role_weighted_starts[role as usize] -= u128::from(rarity) * u128::from(stake_start_time);
+        self.role_weights = role_weights;
+        self.role_weighted_starts = role_weighted_starts;
+    }
+
+    fn is_well_formed(&self) -> bool {
+        BankTierRisk::from_u8(self.bank_tier).is_some()
+            && self.outcome_count as usize <= MAX_BANK_OUTCOMES
+            && self.bank_outcomes.iter().all(|outcome| {
+                outcome.is_negative <= 1
+                    && NegativeOutcome::from_u8(outcome.negative_outcome).is_some()
+            })
+    }
+
+    // Only the first outcome_count slots are live
+    pub fn outcomes(&self) -> &[BankOutcome] {
+        &self.bank_outcomes[..self.outcome_count as usize]
+    }
+
+    pub fn allows(&self, role: RoleType) -> bool {
+        self.allowed_roles & (1 << role as u8) != 0
+    }
+
+    pub fn set_allowed_roles(&mut self, roles: &[RoleType]) {
+        self.allowed_roles = roles.iter().fold(0, |mask, &role| mask | 1 << role as u8);
+    }
+
+    // Caller checks bank_outcomes fits in MAX_BANK_OUTCOMES
+    pub fn set_outcomes(&mut self, bank_outcomes: &[BankOutcome]) {
+        self.bank_outcomes[..bank_outcomes.len()].copy_from_slice(bank_outcomes);
+        self.outcome_count = bank_outcomes.len() as u8;
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[zero_copy]
 pub struct BankOutcome {
+    pub odds: u32,
+    pub payout_multiplier: u8,
+    pub is_negative: u8,
+    pub negative_outcome: u8,
+}
+
+// Borsh form of BankOutcome used in instruction data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct BankOutcomeArgs {
     pub odds: u32,
     pub payout_multiplier: u8,
     pub is_negative: bool,
     pub negative_outcome: NegativeOutcome,
 }
 
+impl From<BankOutcomeArgs> for BankOutcome {
+    fn from(args: BankOutcomeArgs) -> BankOutcome {
+        BankOutcome::new(
+            args.is_negative,
+            args.negative_outcome,
+            args.odds,
+            args.payout_multiplier,
+        )
+    }
+}
+
 impl BankOutcome {
     pub fn new(
         is_negative: bool,
@@ -78,13 +335,21 @@ impl BankOutcome {
         payout_multiplier: u8,
     ) -> BankOutcome {
         BankOutcome {
-            is_negative,
-            negative_outcome,
+            is_negative: is_negative as u8,
+            negative_outcome: negative_outcome as u8,
             odds, // 40%
             payout_multiplier,
         }
     }
 
+    pub fn is_negative(&self) -> bool {
+        self.is_negative != 0
+    }
+
+    pub fn negative_outcome(&self) -> NegativeOutcome {
+        NegativeOutcome::from_u8(self.negative_outcome).expect("outcome written by the program")
+    }
+
     pub fn generate_bank(risk: BankTierRisk) -> Vec<BankOutcome> {
         match risk {
             BankTierRisk::Low => {
@@ -128,6 +393,7 @@ impl BankOutcome {
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[repr(u8)]
 pub enum NegativeOutcome {
     None,
     Fumbled,
@@ -136,57 +402,226 @@ pub enum NegativeOutcome {
     Rekt,
 }
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[repr(u8)]
 pub enum BankTierRisk {
     Low,
     Mid,
     High,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+impl NegativeOutcome {
+    pub fn from_u8(value: u8) -> Option<NegativeOutcome> {
+        match value {
+            0 => Some(NegativeOutcome::None),
+            1 => Some(NegativeOutcome::Fumbled),
+            2 => Some(NegativeOutcome::Confiscation),
+            3 => Some(NegativeOutcome::Arrested),
+            4 => Some(NegativeOutcome::Rekt),
+            _ => None,
+        }
+    }
+}
+
+impl BankTierRisk {
+    pub fn from_u8(value: u8) -> Option<BankTierRisk> {
+        match value {
+            0 => Some(BankTierRisk::Low),
+            1 => Some(BankTierRisk::Mid),
+            2 => Some(BankTierRisk::High),
+            _ => None,
+        }
+    }
+}
+
+#[zero_copy]
 pub struct Role {
-    role_type: RoleType,
+    pub role_type: u8,
+    // Added on top of the bank reward rate, in basis points
+    pub reward_boost_bps: u16,
+    // Taken off the roll, tables list positive outcomes first
+    pub odds_shift: u32,
+    // Taken off ARREST_JAIL_TIME, in seconds
+    pub jail_time_reduction: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
-enum RoleType {
+#[repr(u8)]
+pub enum RoleType {
     Chimp,
     Gorrila,
 }
 
+impl RoleType {
+    pub fn from_u8(value: u8) -> Option<RoleType> {
+        match value {
+            0 => Some(RoleType::Chimp),
+            1 => Some(RoleType::Gorrila),
+            _ => None,
+        }
+    }
+}
+
+// Proof that a mint was published with the given role and rarity
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RoleProof {
+    pub role: RoleType,
+    pub rarity: u16,
+    pub proof: Vec<[u8; 32]>,
+}
+
+// Returned by preview_unstake through return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UnstakePreview {
+    // Accrued so far, before the outcome multiplier
+    pub base_reward: u64,
+    // Odds weighted reward over every outcome
+    pub expected_reward: u64,
+    pub outcomes: Vec<OutcomePreview>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OutcomePreview {
+    // Out of TOTAL_ODDS, after the role's odds shift
+    pub odds: u32,
+    pub payout_multiplier: u8,
+    pub negative_outcome: NegativeOutcome,
+    pub reward: u64,
+}
+
 impl Role {
-    pub fn new() -> Vec<Role> {
-        vec![
-            Role {
-                role_type: RoleType::Chimp,
-            },
-            Role {
-                role_type: RoleType::Gorrila,
-            },
+    pub fn role_type(&self) -> RoleType {
+        RoleType::from_u8(self.role_type).expect("role type written by the program")
+    }
+
+    pub fn new() -> [Role; MAX_ROLES] {
+        [
+            Role::neutral(RoleType::Chimp),
+            Role::neutral(RoleType::Gorrila),
         ]
     }
+
+    fn neutral(role_type: RoleType) -> Role {
+        Role {
+            role_type: role_type as u8,
+            reward_boost_bps: 0,
+            odds_shift: 0,
+            jail_time_reduction: 0,
+        }
+    }
 }
 
+// Rolls land between 0 - 99_999, every bank's odds have to cover all of them
+pub const TOTAL_ODDS: u32 = 100_000;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+pub const BASE_RARITY_WEIGHT: u16 = 10_000;
+
+// Getting arrested keeps the player from staking for 3 days
+pub const ARREST_JAIL_TIME: u64 = 3 * 24 * 3600;
+
+// Players get 2 days to react to a queued config change
+pub const DEFAULT_CHANGE_DELAY: u64 = 2 * 24 * 3600;
+
 const DISCRIMINATOR: usize = 8;
+const VEC_PREFIX: usize = 4;
 const PUBKEY: usize = 32;
 const BOOL: usize = 1;
 const U8: usize = 1;
 const U16: usize = 2;
+const U32: usize = 4;
 const U64: usize = 8;
 
 impl Global {
+    // Zero-copy layout is fixed, tables are sized to their capacity. This
+    // replaces growing Global with realloc: settlement reads banks in place,
+    // so every table gets MAX_BANK_OUTCOMES slots up front and never moves
     pub fn len() -> usize {
-        DISCRIMINATOR + PUBKEY + U64 + U64 + PUBKEY + U8 + PUBKEY + U16
+        DISCRIMINATOR + std::mem::size_of::<Global>()
+    }
+}
+
+impl Blocklist {
+    // Sized for a full list up front, wallets never realloc
+    pub fn len() -> usize {
+        DISCRIMINATOR + VEC_PREFIX + MAX_BLOCKED_WALLETS * PUBKEY
+    }
+}
+
+impl PendingChange {
+    // Sized to the queued change, pending changes are closed rather than edited
+    pub fn len(change: &ConfigChange) -> usize {
+        DISCRIMINATOR + U64 + change.len() + U64 + U64
     }
 }
 
+impl ConfigChange {
+    // Variant tag, then its fields
+    fn len(&self) -> usize {
+        U8 + match self {
+            ConfigChange::UpdateBank { bank_outcomes, .. } => {
+                U8 + U64 + VEC_PREFIX + bank_outcomes.len() * BankOutcomeArgs::LEN
+            }
+            ConfigChange::UpdateBankRoles { allowed_roles, .. } => {
+                U8 + VEC_PREFIX + allowed_roles.len() * U8
+            }
+            ConfigChange::UpdateRole { .. } => U8 + U16 + U32 + U64,
+            ConfigChange::UpdateChangeDelay { .. } => U64,
+            ConfigChange::UpdateRtpBounds { .. } => U32 + U32,
+        }
+    }
+}
+
+impl BankOutcomeArgs {
+    pub const LEN: usize = U32 + U8 + BOOL + U8;
+}
+
 impl PlayerInfo {
     pub fn len() -> usize {
-        DISCRIMINATOR + BOOL + U64 + U16
+        DISCRIMINATOR + BOOL + U64 + U16 + U64
     }
 }
 
 impl PlayerStakeInfo {
+    pub fn len() -> usize {
+        DISCRIMINATOR + PUBKEY + PUBKEY + U8 + U64 + U8 + BOOL + U16
+    }
+}
+
+impl LegacyPlayerStakeInfo {
+    // What the program allocated then, more than the fields it wrote
     pub fn len() -> usize {
         DISCRIMINATOR + PUBKEY + PUBKEY + PUBKEY + U64 + U8 + U8
     }
 }
+
+impl AccountSerialize for LegacyPlayerStakeInfo {
+    fn try_serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(&PlayerStakeInfo::discriminator())
+            .map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+        AnchorSerialize::serialize(self, writer).map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+        Ok(())
+    }
+}
+
+impl AccountDeserialize for LegacyPlayerStakeInfo {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < DISCRIMINATOR || buf[..DISCRIMINATOR] != PlayerStakeInfo::discriminator() {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let mut data: &[u8] = &buf[DISCRIMINATOR..];
+        AnchorDeserialize::deserialize(&mut data)
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl Owner for LegacyPlayerStakeInfo {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
@@ -1,12 +1,19 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::*};
+use anchor_spl::{
+    associated_token::{get_associated_token_address, AssociatedToken},
+    token::*,
+};
+use mpl_bubblegum::program::Bubblegum;
+use spl_account_compression::{program::SplAccountCompression, Noop};
 
-use crate::state::*;
+use crate::{error::ErrorCode, pda::*, state::*};
 
 #[derive(Accounts)]
 pub struct InitOrUpdateCollection<'info> {
-    #[account(init_if_needed, seeds=[b"global"], bump, payer = creator, space= Global::len())]
-    pub global_state: Account<'info, Global>,
+    #[account(init_if_needed, seeds = [GLOBAL_SEED], bump, payer = creator, space= Global::len())]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(init_if_needed, seeds = [BLOCKLIST_SEED], bump, payer = creator, space = Blocklist::len())]
+    pub blocklist: Account<'info, Blocklist>,
     #[account(mut)]
     pub creator: Signer<'info>,
     #[account(
@@ -22,15 +29,13 @@ pub struct InitOrUpdateCollection<'info> {
     pub user_nft_mint: Account<'info, Mint>,
     pub collection_mint: Account<'info, Mint>,
     #[account(
-        constraint = global_reward_token_account.mint == reward_mint.key(),
-        constraint = global_reward_token_account.owner == creator.key()
+        mut,
+        constraint = creator_token_reward_account.mint == reward_mint.key(),
+        constraint = creator_token_reward_account.owner == creator.key()
     )]
     pub creator_token_reward_account: Account<'info, TokenAccount>,
     pub reward_mint: Account<'info, Mint>,
-    #[account(
-        constraint = global_reward_token_account.mint == reward_mint.key(),
-        constraint = global_reward_token_account.owner == global_state.key()
-    )]
+    #[account(mut, address = get_associated_token_address(&global_state.key(), &reward_mint.key()))]
     pub global_reward_token_account: Account<'info, TokenAccount>,
     // ATA Program required to create ATA for pda_nft_account
     // Token Program required to call transfer instruction
@@ -41,28 +46,56 @@ pub struct InitOrUpdateCollection<'info> {
 }
 
 #[derive(Accounts)]
-pub struct UpdateCollectionAuthority<'info> {
-    #[account(mut, seeds=[b"global"], bump)]
-    pub global_state: Account<'info, Global>,
+pub struct ProposeAuthority<'info> {
+    #[account(mut, seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
     #[account(
-        mut,
-        constraint = global_state.update_authority == current_authority.key()
+        constraint = global_state.load()?.update_authority == current_authority.key() @ ErrorCode::InvalidUpdateAuthority
     )]
     pub current_authority: Signer<'info>,
-    #[account(mut)]
-    /// CHECK:
+    /// CHECK: Only recorded, has to sign accept_authority to take over
     pub new_authority: AccountInfo<'info>,
-    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut, seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        constraint = global_state.load()?.pending_authority == new_authority.key() @ ErrorCode::InvalidPendingAuthority
+    )]
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    #[account(mut, seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        constraint = global_state.load()?.update_authority == current_authority.key() @ ErrorCode::InvalidUpdateAuthority
+    )]
+    pub current_authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
 pub struct StakePlayerStakeInfo<'info> {
-    #[account(mut, seeds = [b"global"], bump)]
-    pub global_state: Account<'info, Global>,
-    #[account(init_if_needed, seeds = [b"player", player.key().as_ref()], bump, payer = player, space = PlayerInfo::len())]
+    #[account(
+        mut, seeds = [GLOBAL_SEED], bump,
+        constraint = !global_state.load()?.is_paused() @ ErrorCode::ProgramPaused
+    )]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        seeds = [BLOCKLIST_SEED], bump,
+        constraint = !blocklist.wallets.contains(&player.key()) @ ErrorCode::PlayerBlocked
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    #[account(init_if_needed, seeds = [PLAYER_SEED, player.key().as_ref()], bump, payer = player, space = PlayerInfo::len())]
     pub player_info: Account<'info, PlayerInfo>,
-    #[account(init, seeds = [b"stake_info", player.key().as_ref(), user_nft_account.key().as_ref()], bump, payer = player, space = PlayerStakeInfo::len())]
+    #[account(init, seeds = [STAKE_INFO_SEED, player.key().as_ref(), user_nft_mint.key().as_ref()], bump, payer = player, space = PlayerStakeInfo::len())]
     pub player_stake_info: Account<'info, PlayerStakeInfo>,
+    // Vault the pool pays from, stakes are only admitted while it covers them
+    #[account(address = get_associated_token_address(&global_state.key(), &global_state.load()?.reward_mint))]
+    pub global_reward_token_account: Account<'info, TokenAccount>,
     #[account(
         mut,
         constraint = user_nft_account.owner.key() == player.key(),
@@ -74,7 +107,7 @@ pub struct StakePlayerStakeInfo<'info> {
     /// CHECK: Account will be validated in processor
     pub nft_metadata: AccountInfo<'info>,
     pub user_nft_mint: Account<'info, Mint>,
-    #[account(constraint = collection_mint.key() == global_state.collection)]
+    #[account(constraint = collection_mint.key() == global_state.load()?.collection)]
     pub collection_mint: Account<'info, Mint>,
     #[account(mut)]
     pub player: Signer<'info>,
@@ -86,15 +119,18 @@ pub struct StakePlayerStakeInfo<'info> {
 
 #[derive(Accounts)]
 pub struct UnstakePlayerStakeInfo<'info> {
-    #[account(mut, seeds = [b"global"], bump)]
-    pub global_state: Account<'info, Global>,
     #[account(
-        mut, seeds = [b"player", player.key().as_ref()], bump,
+        mut, seeds = [GLOBAL_SEED], bump,
+        constraint = !global_state.load()?.is_paused() @ ErrorCode::ProgramPaused
+    )]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [PLAYER_SEED, player.key().as_ref()], bump,
         constraint = player_info.active_staked >= 1
     )]
     pub player_info: Account<'info, PlayerInfo>,
     #[account(
-        mut, seeds = [b"stake_info", player.key().as_ref(), nft_mint.key().as_ref()], bump,
+        mut, seeds = [STAKE_INFO_SEED, player.key().as_ref(), nft_mint.key().as_ref()], bump,
         constraint = player_stake_info.mint == nft_mint.key(),
         constraint = player_stake_info.owner == player.key(),
         close = player
@@ -107,12 +143,9 @@ pub struct UnstakePlayerStakeInfo<'info> {
         associated_token::authority = player // If init required, authority set to PDA
     )]
     pub player_reward_token_account: Account<'info, TokenAccount>,
-    #[account(constraint = global_state.reward_mint == reward_mint.key())]
+    #[account(constraint = global_state.load()?.reward_mint == reward_mint.key())]
     pub reward_mint: Account<'info, Mint>,
-    #[account(
-        constraint = global_reward_token_account.mint == reward_mint.key(),
-        constraint = global_reward_token_account.owner == global_state.key()
-    )]
+    #[account(mut, address = get_associated_token_address(&global_state.key(), &reward_mint.key()))]
     pub global_reward_token_account: Account<'info, TokenAccount>,
     #[account(mut)]
     pub player: Signer<'info>,
@@ -127,7 +160,7 @@ pub struct UnstakePlayerStakeInfo<'info> {
     /// CHECK: Account will be validated in processor
     pub nft_metadata: AccountInfo<'info>,
     pub nft_mint: Account<'info, Mint>,
-    #[account(constraint = collection_mint.key() == global_state.collection)]
+    #[account(constraint = collection_mint.key() == global_state.load()?.collection)]
     pub collection_mint: Account<'info, Mint>,
     // ATA Program required to create ATA for pda_nft_account
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -136,3 +169,463 @@ pub struct UnstakePlayerStakeInfo<'info> {
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct StakeCompressedPlayerStakeInfo<'info> {
+    #[account(
+        mut, seeds = [GLOBAL_SEED], bump,
+        constraint = !global_state.load()?.is_paused() @ ErrorCode::ProgramPaused
+    )]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        seeds = [BLOCKLIST_SEED], bump,
+        constraint = !blocklist.wallets.contains(&player.key()) @ ErrorCode::PlayerBlocked
+    )]
+    pub blocklist: Account<'info, Blocklist>,
+    #[account(init_if_needed, seeds = [PLAYER_SEED, player.key().as_ref()], bump, payer = player, space = PlayerInfo::len())]
+    pub player_info: Account<'info, PlayerInfo>,
+    #[account(
+        init,
+        seeds = [STAKE_INFO_SEED, player.key().as_ref(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump, payer = player, space = PlayerStakeInfo::len()
+    )]
+    pub player_stake_info: Account<'info, PlayerStakeInfo>,
+    // Same admission check as token stakes
+    #[account(address = get_associated_token_address(&global_state.key(), &global_state.load()?.reward_mint))]
+    pub global_reward_token_account: Account<'info, TokenAccount>,
+    /// CHECK: Validated by Bubblegum during delegate
+    #[account(seeds = [merkle_tree.key().as_ref()], bump, seeds::program = bubblegum_program.key())]
+    pub tree_authority: UncheckedAccount<'info>,
+    /// CHECK: Validated by the compression program during verify_leaf
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    // Bubblegum required to delegate the leaf, remaining accounts are the proof
+    pub bubblegum_program: Program<'info, Bubblegum>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct UnstakeCompressedPlayerStakeInfo<'info> {
+    #[account(
+        mut, seeds = [GLOBAL_SEED], bump,
+        constraint = !global_state.load()?.is_paused() @ ErrorCode::ProgramPaused
+    )]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [PLAYER_SEED, player.key().as_ref()], bump,
+        constraint = player_info.active_staked >= 1
+    )]
+    pub player_info: Account<'info, PlayerInfo>,
+    #[account(
+        mut,
+        seeds = [STAKE_INFO_SEED, player.key().as_ref(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump,
+        constraint = player_stake_info.is_compressed @ ErrorCode::NotCompressedStake,
+        constraint = player_stake_info.owner == player.key(),
+        close = player
+    )]
+    pub player_stake_info: Account<'info, PlayerStakeInfo>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        associated_token::mint = reward_mint,
+        associated_token::authority = player
+    )]
+    pub player_reward_token_account: Account<'info, TokenAccount>,
+    #[account(constraint = global_state.load()?.reward_mint == reward_mint.key())]
+    pub reward_mint: Account<'info, Mint>,
+    #[account(mut, address = get_associated_token_address(&global_state.key(), &reward_mint.key()))]
+    pub global_reward_token_account: Account<'info, TokenAccount>,
+    /// CHECK: Validated by Bubblegum during delegate
+    #[account(seeds = [merkle_tree.key().as_ref()], bump, seeds::program = bubblegum_program.key())]
+    pub tree_authority: UncheckedAccount<'info>,
+    /// CHECK: Validated by the compression program during delegate
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    // Bubblegum required to hand the leaf delegate back, remaining accounts are the proof
+    pub bubblegum_program: Program<'info, Bubblegum>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CrankStaleStake<'info> {
+    #[account(mut, seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [PLAYER_SEED, player_stake_info.owner.as_ref()], bump,
+        constraint = player_info.active_staked >= 1
+    )]
+    pub player_info: Account<'info, PlayerInfo>,
+    // Compressed stakes have no token account to inspect and are rejected
+    #[account(
+        mut, seeds = [STAKE_INFO_SEED, player_stake_info.owner.as_ref(), nft_mint.key().as_ref()], bump,
+        constraint = !player_stake_info.is_compressed @ ErrorCode::CompressedStake,
+        close = cranker
+    )]
+    pub player_stake_info: Account<'info, PlayerStakeInfo>,
+    // Token account currently holding the staked NFT
+    #[account(constraint = nft_holder_account.mint == player_stake_info.mint)]
+    pub nft_holder_account: Account<'info, TokenAccount>,
+    #[account(constraint = nft_mint.key() == player_stake_info.mint)]
+    pub nft_mint: Account<'info, Mint>,
+    // Anyone can crank, the stake rent is the bounty
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateStakeInfo<'info> {
+    // Adds weight like a stake does, so it waits out a pause too
+    #[account(
+        mut, seeds = [GLOBAL_SEED], bump,
+        constraint = !global_state.load()?.is_paused() @ ErrorCode::ProgramPaused
+    )]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [STAKE_INFO_SEED, player.key().as_ref(), user_nft_account.key().as_ref()], bump,
+        constraint = legacy_player_stake_info.owner == player.key(),
+        constraint = legacy_player_stake_info.mint == user_nft_account.mint,
+        close = player
+    )]
+    pub legacy_player_stake_info: Account<'info, LegacyPlayerStakeInfo>,
+    #[account(
+        init,
+        seeds = [STAKE_INFO_SEED, player.key().as_ref(), legacy_player_stake_info.mint.as_ref()],
+        bump, payer = player, space = PlayerStakeInfo::len()
+    )]
+    pub player_stake_info: Account<'info, PlayerStakeInfo>,
+    // Migrated stakes enter the committed rewards checked against the vault
+    #[account(address = get_associated_token_address(&global_state.key(), &global_state.load()?.reward_mint))]
+    pub global_reward_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = user_nft_account.owner.key() == player.key(),
+        constraint = user_nft_account.amount == 1,
+    )]
+    pub user_nft_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    // Token Program required to move the delegate to the new stake address
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(change_id: u64, change: ConfigChange)]
+pub struct QueueChange<'info> {
+    #[account(mut, seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        init,
+        seeds = [PENDING_CHANGE_SEED, &change_id.to_le_bytes()],
+        bump, payer = operator, space = PendingChange::len(&change)
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    #[account(
+        mut,
+        constraint = global_state.load()?.operator == operator.key() @ ErrorCode::InvalidOperator
+    )]
+    pub operator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    #[account(mut, seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [PENDING_CHANGE_SEED, &pending_change.id.to_le_bytes()], bump,
+        close = operator
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    // A change only executes while the vault still covers the live stakes
+    #[account(address = get_associated_token_address(&global_state.key(), &global_state.load()?.reward_mint))]
+    pub global_reward_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = global_state.load()?.operator == operator.key() @ ErrorCode::InvalidOperator
+    )]
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    #[account(seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [PENDING_CHANGE_SEED, &pending_change.id.to_le_bytes()], bump,
+        close = update_authority
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    #[account(
+        mut,
+        constraint = global_state.load()?.update_authority == update_authority.key() @ ErrorCode::InvalidUpdateAuthority
+    )]
+    pub update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PublishRoleMerkleRoot<'info> {
+    #[account(mut, seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(constraint = global_state.load()?.operator == operator.key() @ ErrorCode::InvalidOperator)]
+    pub operator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePermissions<'info> {
+    #[account(mut, seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        constraint = global_state.load()?.update_authority == update_authority.key() @ ErrorCode::InvalidUpdateAuthority
+    )]
+    pub update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(constraint = global_state.load()?.treasurer == treasurer.key() @ ErrorCode::InvalidTreasurer)]
+    pub treasurer: Signer<'info>,
+    #[account(mut, address = get_associated_token_address(&global_state.key(), &global_state.load()?.reward_mint))]
+    pub global_reward_token_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = destination_token_account.mint == global_state.load()?.reward_mint)]
+    pub destination_token_account: Account<'info, TokenAccount>,
+    // Token Program required to call transfer instruction
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    #[account(mut, seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(constraint = global_state.load()?.pauser == pauser.key() @ ErrorCode::InvalidPauser)]
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyUnstake<'info> {
+    #[account(
+        mut, seeds = [GLOBAL_SEED], bump,
+        constraint = global_state.load()?.is_paused() @ ErrorCode::ProgramNotPaused
+    )]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [PLAYER_SEED, player.key().as_ref()], bump,
+        constraint = player_info.active_staked >= 1
+    )]
+    pub player_info: Account<'info, PlayerInfo>,
+    #[account(
+        mut, seeds = [STAKE_INFO_SEED, player.key().as_ref(), player_stake_info.mint.as_ref()], bump,
+        constraint = !player_stake_info.is_compressed @ ErrorCode::CompressedStake,
+        constraint = player_stake_info.owner == player.key(),
+        close = player
+    )]
+    pub player_stake_info: Account<'info, PlayerStakeInfo>,
+    #[account(
+        mut,
+        constraint = user_nft_account.owner.key() == player.key(),
+        constraint = user_nft_account.mint == player_stake_info.mint,
+    )]
+    pub user_nft_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    // Token Program required to call revoke instruction
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct EmergencyUnstakeCompressed<'info> {
+    #[account(
+        mut, seeds = [GLOBAL_SEED], bump,
+        constraint = global_state.load()?.is_paused() @ ErrorCode::ProgramNotPaused
+    )]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [PLAYER_SEED, player.key().as_ref()], bump,
+        constraint = player_info.active_staked >= 1
+    )]
+    pub player_info: Account<'info, PlayerInfo>,
+    #[account(
+        mut,
+        seeds = [STAKE_INFO_SEED, player.key().as_ref(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump,
+        constraint = player_stake_info.is_compressed @ ErrorCode::NotCompressedStake,
+        constraint = player_stake_info.owner == player.key(),
+        close = player
+    )]
+    pub player_stake_info: Account<'info, PlayerStakeInfo>,
+    /// CHECK: Validated by Bubblegum during delegate
+    #[account(seeds = [merkle_tree.key().as_ref()], bump, seeds::program = bubblegum_program.key())]
+    pub tree_authority: UncheckedAccount<'info>,
+    /// CHECK: Validated by the compression program during delegate
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    // Bubblegum required to hand the leaf delegate back, remaining accounts are the proof
+    pub bubblegum_program: Program<'info, Bubblegum>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewUnstake<'info> {
+    #[account(seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        seeds = [STAKE_INFO_SEED, player_stake_info.owner.as_ref(), player_stake_info.mint.as_ref()],
+        bump
+    )]
+    pub player_stake_info: Account<'info, PlayerStakeInfo>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBlocklist<'info> {
+    #[account(seeds = [GLOBAL_SEED], bump)]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(mut, seeds = [BLOCKLIST_SEED], bump)]
+    pub blocklist: Account<'info, Blocklist>,
+    #[account(
+        constraint = global_state.load()?.update_authority == update_authority.key() @ ErrorCode::InvalidUpdateAuthority
+    )]
+    pub update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(forfeit_rewards: bool)]
+pub struct ForceUnstake<'info> {
+    // Forfeiting pays nothing out, it can clear a stake during a pause
+    #[account(
+        mut, seeds = [GLOBAL_SEED], bump,
+        constraint = forfeit_rewards || !global_state.load()?.is_paused() @ ErrorCode::ProgramPaused
+    )]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [PLAYER_SEED, player_stake_info.owner.as_ref()], bump,
+        constraint = player_info.active_staked >= 1
+    )]
+    pub player_info: Account<'info, PlayerInfo>,
+    #[account(
+        mut,
+        seeds = [STAKE_INFO_SEED, player_stake_info.owner.as_ref(), player_stake_info.mint.as_ref()],
+        bump,
+        constraint = !player_stake_info.is_compressed @ ErrorCode::CompressedStake,
+        close = owner
+    )]
+    pub player_stake_info: Account<'info, PlayerStakeInfo>,
+    /// CHECK: Stake owner, only receives the stake rent
+    #[account(mut, constraint = owner.key() == player_stake_info.owner)]
+    pub owner: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = user_nft_account.owner == player_stake_info.owner,
+        constraint = user_nft_account.mint == player_stake_info.mint,
+    )]
+    pub user_nft_account: Account<'info, TokenAccount>,
+    #[account(constraint = nft_mint.key() == player_stake_info.mint)]
+    pub nft_mint: Account<'info, Mint>,
+    // Only the token owner can revoke, the stake moves the NFT through this
+    // account and back so spending the delegated amount clears the delegate
+    #[account(
+        init_if_needed,
+        payer = update_authority,
+        associated_token::mint = nft_mint,
+        associated_token::authority = player_stake_info
+    )]
+    pub escrow_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = player_reward_token_account.mint == global_state.load()?.reward_mint,
+        constraint = player_reward_token_account.owner == player_stake_info.owner
+    )]
+    pub player_reward_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = get_associated_token_address(&global_state.key(), &global_state.load()?.reward_mint))]
+    pub global_reward_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = global_state.load()?.update_authority == update_authority.key() @ ErrorCode::InvalidUpdateAuthority
+    )]
+    pub update_authority: Signer<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    // Token Program required to clear the delegate and pay out unforfeited rewards
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    nonce: u64,
+    index: u32,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    forfeit_rewards: bool
+)]
+pub struct ForceUnstakeCompressed<'info> {
+    #[account(
+        mut, seeds = [GLOBAL_SEED], bump,
+        constraint = forfeit_rewards || !global_state.load()?.is_paused() @ ErrorCode::ProgramPaused
+    )]
+    pub global_state: AccountLoader<'info, Global>,
+    #[account(
+        mut, seeds = [PLAYER_SEED, owner.key().as_ref()], bump,
+        constraint = player_info.active_staked >= 1
+    )]
+    pub player_info: Account<'info, PlayerInfo>,
+    #[account(
+        mut,
+        seeds = [STAKE_INFO_SEED, owner.key().as_ref(), get_asset_id(&merkle_tree.key(), nonce).as_ref()],
+        bump,
+        constraint = player_stake_info.is_compressed @ ErrorCode::NotCompressedStake,
+        constraint = player_stake_info.owner == owner.key(),
+        close = owner
+    )]
+    pub player_stake_info: Account<'info, PlayerStakeInfo>,
+    /// CHECK: Stake owner, receives the stake rent and the leaf back
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = player_reward_token_account.mint == global_state.load()?.reward_mint,
+        constraint = player_reward_token_account.owner == owner.key()
+    )]
+    pub player_reward_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = get_associated_token_address(&global_state.key(), &global_state.load()?.reward_mint))]
+    pub global_reward_token_account: Account<'info, TokenAccount>,
+    /// CHECK: Validated by Bubblegum during transfer
+    #[account(seeds = [merkle_tree.key().as_ref()], bump, seeds::program = bubblegum_program.key())]
+    pub tree_authority: UncheckedAccount<'info>,
+    /// CHECK: Validated by the compression program during transfer
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    #[account(
+        constraint = global_state.load()?.update_authority == update_authority.key() @ ErrorCode::InvalidUpdateAuthority
+    )]
+    pub update_authority: Signer<'info>,
+    // Bubblegum required to clear the leaf delegate, remaining accounts are the proof
+    pub bubblegum_program: Program<'info, Bubblegum>,
+    pub compression_program: Program<'info, SplAccountCompression>,
+    pub log_wrapper: Program<'info, Noop>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
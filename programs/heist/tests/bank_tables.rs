@@ -0,0 +1,114 @@
+mod common;
+
+use common::*;
+use heist::{error::ErrorCode, state::*};
+use heist_client::instructions;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn bank_tables_list_positive_outcomes_first() {
+    let mut pool = start_pool().await;
+    let operator = pool.context.payer.pubkey();
+    let change = ConfigChange::UpdateBank {
+        bank_tier_risk: BankTierRisk::Mid,
+        reward_per_hour: REWARD_PER_HOUR,
+        bank_outcomes: vec![
+            BankOutcomeArgs {
+                odds: 40_000,
+                payout_multiplier: 0,
+                is_negative: true,
+                negative_outcome: NegativeOutcome::Fumbled,
+            },
+            BankOutcomeArgs {
+                odds: 60_000,
+                payout_multiplier: 2,
+                is_negative: false,
+                negative_outcome: NegativeOutcome::None,
+            },
+        ],
+    };
+
+    let result = process(
+        &mut pool.context,
+        &[instructions::queue_change(&operator, 0, change)],
+        &[],
+    )
+    .await;
+    assert_error(result, ErrorCode::InvalidOutcomeOrder);
+}
+
+#[tokio::test]
+async fn bank_tables_are_checked_against_the_bounds_they_execute_under() {
+    let mut pool = start_pool().await;
+    let operator = pool.context.payer.pubkey();
+    let bounds = |min_rtp_bps, max_rtp_bps| ConfigChange::UpdateRtpBounds {
+        min_rtp_bps,
+        max_rtp_bps,
+    };
+    let even_table = ConfigChange::UpdateBank {
+        bank_tier_risk: BankTierRisk::Mid,
+        reward_per_hour: REWARD_PER_HOUR,
+        bank_outcomes: vec![BankOutcomeArgs {
+            odds: 100_000,
+            payout_multiplier: 1,
+            is_negative: false,
+            negative_outcome: NegativeOutcome::None,
+        }],
+    };
+
+    // The table pays 100.00%, outside the bounds in force while it is queued
+    let changes = [bounds(9_000, 9_500), bounds(10_000, 10_000), even_table];
+    for (change_id, change) in changes.into_iter().enumerate() {
+        process(
+            &mut pool.context,
+            &[instructions::queue_change(
+                &operator,
+                change_id as u64,
+                change,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        if change_id == 0 {
+            warp_forward(&mut pool.context, DEFAULT_CHANGE_DELAY as i64).await;
+            process(
+                &mut pool.context,
+                &[instructions::execute_change(
+                    &operator,
+                    0,
+                    &pool.reward_mint,
+                )],
+                &[],
+            )
+            .await
+            .unwrap();
+        }
+    }
+    warp_forward(&mut pool.context, DEFAULT_CHANGE_DELAY as i64).await;
+
+    let result = process(
+        &mut pool.context,
+        &[instructions::execute_change(
+            &operator,
+            2,
+            &pool.reward_mint,
+        )],
+        &[],
+    )
+    .await;
+    assert_error(result, ErrorCode::RtpOutOfBounds);
+    for change_id in [1, 2] {
+        process(
+            &mut pool.context,
+            &[instructions::execute_change(
+                &operator,
+                change_id,
+                &pool.reward_mint,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+// Compute unit bench, needs the BPF build with the bench feature:
+//   anchor build -- --features bench
+//   BPF_OUT_DIR=$PWD/target/deploy cargo test -p heist --test bench -- --ignored --nocapture
+// Results against the Borsh layout are recorded in BENCH.md
+mod common;
+
+use common::*;
+use heist::state::*;
+use heist_client::instructions;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+// Default per-instruction budget, settlement has to leave room for the CPIs around it
+const COMPUTE_BUDGET: u64 = 200_000;
+
+struct Measurement {
+    total: u64,
+    // Between the sol_log_compute_units calls the bench feature adds to settle_stake
+    settlement: Option<u64>,
+}
+
+async fn measure(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Measurement {
+    let blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    context.last_blockhash = blockhash;
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+
+    let simulation = context
+        .banks_client
+        .simulate_transaction(transaction)
+        .await
+        .unwrap();
+    simulation.result.unwrap().unwrap();
+    let details = simulation.simulation_details.unwrap();
+
+    let remaining: Vec<u64> = details
+        .logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program consumption: "))
+        .filter_map(|log| log.strip_suffix(" units remaining"))
+        .map(|units| units.parse().unwrap())
+        .collect();
+    Measurement {
+        total: details.units_consumed,
+        settlement: match remaining[..] {
+            [before, after, ..] => Some(before - after),
+            _ => None,
+        },
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn stake_and_settlement_compute_units() {
+    let mut pool = open_pool(start_bpf().await).await;
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    let role_proof = publish_role(&mut pool.context, &nft.mint).await;
+
+    let stake_ix = instructions::stake_player_stake_info(
+        &player.pubkey(),
+        &nft.token_account,
+        &nft.mint,
+        &pool.collection.mint,
+        &pool.reward_mint,
+        BankTierRisk::High,
+        role_proof,
+    );
+    let stake = measure(&mut pool.context, &[stake_ix.clone()], &[&player]).await;
+    process(&mut pool.context, &[stake_ix], &[&player])
+        .await
+        .unwrap();
+
+    warp_forward(&mut pool.context, 24 * 3600).await;
+    let unstake_ix = instructions::unstake_player_stake_info(
+        &player.pubkey(),
+        &nft.token_account,
+        &nft.mint,
+        &pool.collection.mint,
+        &pool.reward_mint,
+    );
+    let unstake = measure(&mut pool.context, &[unstake_ix], &[&player]).await;
+
+    println!("| instruction | compute units |");
+    println!("| stake | {} |", stake.total);
+    println!("| unstake | {} |", unstake.total);
+    match unstake.settlement {
+        Some(units) => println!("| settle_stake | {} |", units),
+        None => println!("settle_stake not logged, build with --features bench"),
+    }
+    assert!(stake.total < COMPUTE_BUDGET);
+    assert!(unstake.total < COMPUTE_BUDGET);
+}
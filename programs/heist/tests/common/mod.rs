@@ -0,0 +1,443 @@
+#![allow(dead_code)]
+
+use anchor_lang::{
+    prelude::{AccountInfo, Clock, Pubkey},
+    solana_program::{entrypoint::ProgramResult, program_pack::Pack, system_instruction},
+};
+use heist::{merkle::role_leaf, state::*};
+use heist_client::{decode, instructions, pda::*};
+use heist_fixtures::NftBuilder;
+use mpl_token_metadata::{
+    instruction as metadata_instruction,
+    pda::{find_master_edition_account, find_metadata_account},
+    state::{Collection, Creator},
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+
+pub const REWARD_PER_HOUR: u64 = 1;
+pub const SEASON: i64 = 10 * 24 * 3600;
+// Minted into the vault on top of what init deposits, so stakes are admitted
+pub const VAULT_TOP_UP: u64 = 1_000_000;
+
+pub struct Nft {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub metadata: Pubkey,
+    pub edition: Pubkey,
+}
+
+// Pool owned by the context payer, whose NFT opened it
+pub struct Pool {
+    pub context: ProgramTestContext,
+    pub collection: Nft,
+    pub creator_nft: Nft,
+    pub reward_mint: Pubkey,
+}
+
+pub async fn start() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new("heist", heist::ID, processor!(heist::entry));
+    add_token_metadata(&mut program_test);
+    program_test.start_with_context().await
+}
+
+// Loads heist.so from BPF_OUT_DIR, compute units are only metered there
+pub async fn start_bpf() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new("heist", heist::ID, None);
+    add_token_metadata(&mut program_test);
+    program_test.start_with_context().await
+}
+
+fn add_token_metadata(program_test: &mut ProgramTest) {
+    program_test.add_program(
+        "mpl_token_metadata",
+        mpl_token_metadata::ID,
+        processor!(process_token_metadata),
+    );
+}
+
+// Metaplex ties every lifetime together, the leaked copies outlive the call
+fn process_token_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let program_id = Box::leak(Box::new(*program_id));
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    mpl_token_metadata::processor::process_instruction(program_id, accounts, data)
+}
+
+pub async fn start_pool() -> Pool {
+    open_pool(start().await).await
+}
+
+pub async fn open_pool(mut context: ProgramTestContext) -> Pool {
+    let creator = context.payer.pubkey();
+    let collection = create_nft(&mut context, &creator, None).await;
+    let creator_nft = create_nft(&mut context, &creator, Some(&collection)).await;
+    open_pool_with(context, collection, creator_nft).await
+}
+
+// Opened by the context payer, who has to own creator_nft
+pub async fn open_pool_with(
+    mut context: ProgramTestContext,
+    collection: Nft,
+    creator_nft: Nft,
+) -> Pool {
+    let creator = context.payer.pubkey();
+    let reward_mint = create_mint(&mut context, 0).await;
+    let (global, _) = find_global_address();
+    create_token_account(&mut context, &global, &reward_mint).await;
+    let creator_rewards = create_token_account(&mut context, &creator, &reward_mint).await;
+    mint_to(&mut context, &reward_mint, &creator_rewards, u64::MAX / 2).await;
+
+    let end_date = now(&mut context).await + SEASON;
+    process(
+        &mut context,
+        &[instructions::init_or_update_collection(
+            &creator,
+            &creator_nft.token_account,
+            &creator_nft.mint,
+            &collection.mint,
+            &reward_mint,
+            end_date as u64,
+            REWARD_PER_HOUR,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let vault = get_global_reward_token_address(&reward_mint);
+    mint_to(&mut context, &reward_mint, &vault, VAULT_TOP_UP).await;
+
+    Pool {
+        context,
+        collection,
+        creator_nft,
+        reward_mint,
+    }
+}
+
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await?;
+    context.last_blockhash = blockhash;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+pub fn assert_error(result: Result<(), BanksClientError>, code: impl Into<u32>) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(code.into()))
+    );
+}
+
+pub async fn now(context: &mut ProgramTestContext) -> i64 {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp
+}
+
+pub async fn warp_forward(context: &mut ProgramTestContext, seconds: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += seconds;
+    context.set_sysvar(&clock);
+}
+
+pub async fn create_player(context: &mut ProgramTestContext) -> Keypair {
+    let player = Keypair::new();
+    process(
+        context,
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &player.pubkey(),
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    player
+}
+
+pub async fn create_mint(context: &mut ProgramTestContext, decimals: u8) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    process(
+        context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &payer,
+                Some(&payer),
+                decimals,
+            )
+            .unwrap(),
+        ],
+        &[&mint],
+    )
+    .await
+    .unwrap();
+    mint.pubkey()
+}
+
+pub async fn create_token_account(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Pubkey {
+    let payer = context.payer.pubkey();
+    process(
+        context,
+        &[create_associated_token_account(&payer, owner, mint)],
+        &[],
+    )
+    .await
+    .unwrap();
+    get_associated_token_address(owner, mint)
+}
+
+pub async fn mint_to(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) {
+    let payer = context.payer.pubkey();
+    process(
+        context,
+        &[
+            spl_token::instruction::mint_to(&spl_token::ID, mint, destination, &payer, &[], amount)
+                .unwrap(),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+pub async fn token_account(
+    context: &mut ProgramTestContext,
+    token_account: &Pubkey,
+) -> spl_token::state::Account {
+    let account = context
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap()
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    self::token_account(context, token_account).await.amount
+}
+
+// Injected as Metaplex would leave it, verified into the collection if given
+pub async fn create_nft(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    collection: Option<&Nft>,
+) -> Nft {
+    let mut builder = NftBuilder::new(*owner).creator(context.payer.pubkey(), true, 100);
+    if let Some(collection) = collection {
+        builder = builder.collection(collection.mint, true);
+    }
+    let fixture = builder.build();
+    fixture.set_in(context);
+
+    Nft {
+        mint: fixture.mint,
+        token_account: fixture.token_account,
+        metadata: fixture.metadata,
+        edition: fixture.edition,
+    }
+}
+
+// Minted through the Metaplex program, the context payer is update authority,
+// sole creator and the collection's authority
+pub async fn mint_nft(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    collection: Option<&Nft>,
+    verify: bool,
+) -> Nft {
+    let payer = context.payer.pubkey();
+    let mint = create_mint(context, 0).await;
+    let token_account = create_token_account(context, owner, &mint).await;
+    mint_to(context, &mint, &token_account, 1).await;
+    let (metadata, _) = find_metadata_account(&mint);
+    let (edition, _) = find_master_edition_account(&mint);
+
+    let mut instructions = vec![
+        metadata_instruction::create_metadata_accounts_v3(
+            mpl_token_metadata::ID,
+            metadata,
+            mint,
+            payer,
+            payer,
+            payer,
+            "Heist".to_string(),
+            "HEIST".to_string(),
+            "https://example.com/heist.json".to_string(),
+            Some(vec![Creator {
+                address: payer,
+                verified: true,
+                share: 100,
+            }]),
+            0,
+            true,
+            true,
+            collection.map(|collection| Collection {
+                key: collection.mint,
+                verified: false,
+            }),
+            None,
+            None,
+        ),
+        metadata_instruction::create_master_edition_v3(
+            mpl_token_metadata::ID,
+            edition,
+            mint,
+            payer,
+            payer,
+            metadata,
+            payer,
+            Some(0),
+        ),
+    ];
+    if let Some(collection) = collection.filter(|_| verify) {
+        instructions.push(metadata_instruction::verify_collection(
+            mpl_token_metadata::ID,
+            metadata,
+            payer,
+            payer,
+            collection.mint,
+            collection.metadata,
+            collection.edition,
+            None,
+        ));
+    }
+    process(context, &instructions, &[]).await.unwrap();
+
+    Nft {
+        mint,
+        token_account,
+        metadata,
+        edition,
+    }
+}
+
+// Single leaf tree, the root is the leaf and the proof is empty
+pub async fn publish_role(context: &mut ProgramTestContext, mint: &Pubkey) -> RoleProof {
+    let operator = context.payer.pubkey();
+    let root = role_leaf(mint, RoleType::Chimp, BASE_RARITY_WEIGHT);
+    process(
+        context,
+        &[instructions::publish_role_merkle_root(&operator, root)],
+        &[],
+    )
+    .await
+    .unwrap();
+    RoleProof {
+        role: RoleType::Chimp,
+        rarity: BASE_RARITY_WEIGHT,
+        proof: vec![],
+    }
+}
+
+pub async fn global(context: &mut ProgramTestContext) -> Global {
+    let account = context
+        .banks_client
+        .get_account(find_global_address().0)
+        .await
+        .unwrap()
+        .unwrap();
+    decode::global(&account.data).unwrap()
+}
+
+pub async fn player_info(context: &mut ProgramTestContext, player: &Pubkey) -> PlayerInfo {
+    let account = context
+        .banks_client
+        .get_account(find_player_info_address(player).0)
+        .await
+        .unwrap()
+        .unwrap();
+    decode::player_info(&account.data).unwrap()
+}
+
+pub async fn stake(
+    pool: &mut Pool,
+    player: &Keypair,
+    nft: &Nft,
+    bank_tier_risk: BankTierRisk,
+) -> Result<(), BanksClientError> {
+    let role_proof = publish_role(&mut pool.context, &nft.mint).await;
+    process(
+        &mut pool.context,
+        &[instructions::stake_player_stake_info(
+            &player.pubkey(),
+            &nft.token_account,
+            &nft.mint,
+            &pool.collection.mint,
+            &pool.reward_mint,
+            bank_tier_risk,
+            role_proof,
+        )],
+        &[player],
+    )
+    .await
+}
+
+pub async fn transfer_nft(
+    context: &mut ProgramTestContext,
+    owner: &Keypair,
+    nft: &Nft,
+    to: &Pubkey,
+) -> Pubkey {
+    let destination = create_token_account(context, to, &nft.mint).await;
+    process(
+        context,
+        &[spl_token::instruction::transfer(
+            &spl_token::ID,
+            &nft.token_account,
+            &destination,
+            &owner.pubkey(),
+            &[],
+            1,
+        )
+        .unwrap()],
+        &[owner],
+    )
+    .await
+    .unwrap();
+    destination
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::*;
+use heist::{error::ErrorCode, state::*};
+use heist_client::{instructions, pda::*};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn crank_closes_the_stake_of_a_moved_nft() {
+    let mut pool = start_pool().await;
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    stake(&mut pool, &player, &nft, BankTierRisk::Low)
+        .await
+        .unwrap();
+    let cranker = create_player(&mut pool.context).await;
+
+    // Still held by the player under the stake delegate
+    let result = process(
+        &mut pool.context,
+        &[instructions::crank_stale_stake(
+            &cranker.pubkey(),
+            &player.pubkey(),
+            &nft.mint,
+            &nft.token_account,
+        )],
+        &[&cranker],
+    )
+    .await;
+    assert_error(result, ErrorCode::StakeNotStale);
+
+    let buyer = create_player(&mut pool.context).await;
+    let holder = transfer_nft(&mut pool.context, &player, &nft, &buyer.pubkey()).await;
+    process(
+        &mut pool.context,
+        &[instructions::crank_stale_stake(
+            &cranker.pubkey(),
+            &player.pubkey(),
+            &nft.mint,
+            &holder,
+        )],
+        &[&cranker],
+    )
+    .await
+    .unwrap();
+
+    let global = global(&mut pool.context).await;
+    assert_eq!({ global.bank(BankTierRisk::Low).total_staked }, 0);
+    assert_eq!({ global.total_player }, 0);
+    let stake = find_player_stake_info_address(&player.pubkey(), &nft.mint).0;
+    assert!(pool
+        .context
+        .banks_client
+        .get_account(stake)
+        .await
+        .unwrap()
+        .is_none());
+}
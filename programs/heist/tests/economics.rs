@@ -0,0 +1,57 @@
+use heist::economics::{accrued_reward, shifted_odds, TableStats};
+use heist::state::*;
+
+#[test]
+fn default_tables_pay_the_bank_rate() {
+    for risk in [BankTierRisk::Low, BankTierRisk::Mid, BankTierRisk::High] {
+        let bank = Bank::new(risk, 100, &BankOutcome::generate_bank(risk));
+        let stats = TableStats::from_bank(&bank);
+
+        assert_eq!(stats.rtp_bps(), 10_000);
+        assert_eq!(stats.expected_multiplier(), 1.0);
+    }
+}
+
+#[test]
+fn ruin_counts_outcomes_that_pay_nothing() {
+    let args = [
+        BankOutcomeArgs {
+            odds: 60_000,
+            payout_multiplier: 2,
+            is_negative: false,
+            negative_outcome: NegativeOutcome::None,
+        },
+        BankOutcomeArgs {
+            odds: 40_000,
+            payout_multiplier: 0,
+            is_negative: true,
+            negative_outcome: NegativeOutcome::Fumbled,
+        },
+    ];
+    let stats = TableStats::from_args(&args);
+
+    assert_eq!(stats.weighted_multiplier, 120_000);
+    assert_eq!(stats.rtp_bps(), 12_000);
+    assert_eq!(stats.ruin_probability_bps(), 4_000);
+}
+
+#[test]
+fn odds_shift_moves_odds_to_the_first_outcome() {
+    let odds = [50_000, 30_000, 20_000];
+
+    assert_eq!(shifted_odds(odds, 0), vec![50_000, 30_000, 20_000]);
+    assert_eq!(shifted_odds(odds, 5_000), vec![55_000, 30_000, 15_000]);
+    assert_eq!(shifted_odds(odds, TOTAL_ODDS), vec![TOTAL_ODDS, 0, 0]);
+}
+
+#[test]
+fn accrued_reward_counts_whole_hours() {
+    assert_eq!(
+        accrued_reward(100, 0, BASE_RARITY_WEIGHT, 3 * 3600 + 3599),
+        300
+    );
+    assert_eq!(
+        accrued_reward(100, 1_000, BASE_RARITY_WEIGHT * 2, 3600),
+        220
+    );
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::*;
+use heist::{error::ErrorCode, state::*};
+use heist_client::instructions;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn force_unstake_clears_the_delegate() {
+    let mut pool = start_pool().await;
+    let authority = pool.context.payer.pubkey();
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    stake(&mut pool, &player, &nft, BankTierRisk::Low)
+        .await
+        .unwrap();
+    create_token_account(&mut pool.context, &player.pubkey(), &pool.reward_mint).await;
+    let force_unstake = |forfeit_rewards| {
+        instructions::force_unstake(
+            &authority,
+            &player.pubkey(),
+            &nft.mint,
+            &nft.token_account,
+            &pool.reward_mint,
+            forfeit_rewards,
+        )
+    };
+
+    // Settling pays out so it waits for the pause to lift, forfeiting doesn't
+    process(&mut pool.context, &[instructions::pause(&authority)], &[])
+        .await
+        .unwrap();
+    let result = process(&mut pool.context, &[force_unstake(false)], &[]).await;
+    assert_error(result, ErrorCode::ProgramPaused);
+    process(&mut pool.context, &[force_unstake(true)], &[])
+        .await
+        .unwrap();
+    let nft_account = token_account(&mut pool.context, &nft.token_account).await;
+    assert_eq!(nft_account.amount, 1);
+    assert!(nft_account.delegate.is_none());
+    let player_info = player_info(&mut pool.context, &player.pubkey()).await;
+    assert_eq!(player_info.active_staked, 0);
+}
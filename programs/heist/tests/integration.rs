@@ -0,0 +1,346 @@
+mod common;
+
+use common::*;
+use heist::{error::ErrorCode, state::*};
+use heist_client::{instructions, pda::*};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn init_funds_the_vault() {
+    let mut pool = start_pool().await;
+    let creator = pool.context.payer.pubkey();
+    let global = global(&mut pool.context).await;
+
+    assert!(global.is_initialized());
+    assert_eq!({ global.collection }, pool.collection.mint);
+    assert_eq!({ global.reward_mint }, pool.reward_mint);
+    assert_eq!({ global.update_authority }, creator);
+    assert_eq!({ global.total_player }, 0);
+
+    let vault = get_global_reward_token_address(&pool.reward_mint);
+    let funded = token_balance(&mut pool.context, &vault).await;
+    assert_eq!(
+        funded - VAULT_TOP_UP,
+        (SEASON / 3600) as u64 * MAX_BANKS as u64 * REWARD_PER_HOUR
+    );
+}
+
+#[tokio::test]
+async fn init_rejects_an_end_date_in_the_past() {
+    let mut pool = start_pool().await;
+    let creator = pool.context.payer.pubkey();
+    let end_date = now(&mut pool.context).await - 1;
+
+    let result = process(
+        &mut pool.context,
+        &[instructions::init_or_update_collection(
+            &creator,
+            &pool.creator_nft.token_account,
+            &pool.creator_nft.mint,
+            &pool.collection.mint,
+            &pool.reward_mint,
+            end_date as u64,
+            REWARD_PER_HOUR,
+        )],
+        &[],
+    )
+    .await;
+
+    assert_error(result, ErrorCode::EndDateInPast);
+}
+
+#[tokio::test]
+async fn stake_and_unstake_settles_rewards() {
+    let mut pool = start_pool().await;
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    let role_proof = publish_role(&mut pool.context, &nft.mint).await;
+
+    process(
+        &mut pool.context,
+        &[instructions::stake_player_stake_info(
+            &player.pubkey(),
+            &nft.token_account,
+            &nft.mint,
+            &pool.collection.mint,
+            &pool.reward_mint,
+            BankTierRisk::Low,
+            role_proof,
+        )],
+        &[&player],
+    )
+    .await
+    .unwrap();
+
+    let global_after_stake = global(&mut pool.context).await;
+    assert_eq!(
+        { global_after_stake.bank(BankTierRisk::Low).total_staked },
+        1
+    );
+    assert_eq!({ global_after_stake.total_player }, 1);
+    assert_eq!(
+        player_info(&mut pool.context, &player.pubkey())
+            .await
+            .active_staked,
+        1
+    );
+
+    warp_forward(&mut pool.context, 5 * 3600).await;
+    process(
+        &mut pool.context,
+        &[instructions::unstake_player_stake_info(
+            &player.pubkey(),
+            &nft.token_account,
+            &nft.mint,
+            &pool.collection.mint,
+            &pool.reward_mint,
+        )],
+        &[&player],
+    )
+    .await
+    .unwrap();
+
+    let global_after_unstake = global(&mut pool.context).await;
+    assert_eq!(
+        { global_after_unstake.bank(BankTierRisk::Low).total_staked },
+        0
+    );
+    assert_eq!({ global_after_unstake.total_player }, 0);
+
+    // Whatever the roll, the payout is a table multiple of 5 hours at the bank rate
+    let player_info = player_info(&mut pool.context, &player.pubkey()).await;
+    let rewards = get_player_reward_token_address(&player.pubkey(), &pool.reward_mint);
+    let paid = token_balance(&mut pool.context, &rewards).await;
+    assert_eq!(player_info.active_staked, 0);
+    assert_eq!(player_info.point_balance, paid);
+    assert!([0, 1, 2, 5, 10].contains(&(paid / (5 * REWARD_PER_HOUR))));
+
+    let stake = find_player_stake_info_address(&player.pubkey(), &nft.mint).0;
+    assert!(pool
+        .context
+        .banks_client
+        .get_account(stake)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn stake_rejects_other_collections() {
+    let mut pool = start_pool().await;
+    let player = create_player(&mut pool.context).await;
+    let payer = pool.context.payer.pubkey();
+    let other_collection = create_nft(&mut pool.context, &payer, None).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&other_collection)).await;
+    let role_proof = publish_role(&mut pool.context, &nft.mint).await;
+
+    let result = process(
+        &mut pool.context,
+        &[instructions::stake_player_stake_info(
+            &player.pubkey(),
+            &nft.token_account,
+            &nft.mint,
+            &pool.collection.mint,
+            &pool.reward_mint,
+            BankTierRisk::Low,
+            role_proof,
+        )],
+        &[&player],
+    )
+    .await;
+
+    assert_error(result, ErrorCode::MismatchCollection);
+}
+
+#[tokio::test]
+async fn stake_rejects_metadata_of_another_mint() {
+    let mut pool = start_pool().await;
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    let other = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    let role_proof = publish_role(&mut pool.context, &nft.mint).await;
+
+    let mut stake = instructions::stake_player_stake_info(
+        &player.pubkey(),
+        &nft.token_account,
+        &nft.mint,
+        &pool.collection.mint,
+        &pool.reward_mint,
+        BankTierRisk::Low,
+        role_proof,
+    );
+    let nft_metadata = stake
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == nft.metadata)
+        .unwrap();
+    nft_metadata.pubkey = other.metadata;
+    let result = process(&mut pool.context, &[stake], &[&player]).await;
+
+    assert_error(result, ErrorCode::InvalidMintAddress);
+}
+
+#[tokio::test]
+async fn authority_transfer_needs_the_new_authority() {
+    let mut pool = start_pool().await;
+    let creator = pool.context.payer.pubkey();
+    let new_authority = create_player(&mut pool.context).await;
+    // Delegated before the handover, this role stays where it is
+    let treasurer = create_player(&mut pool.context).await;
+    process(
+        &mut pool.context,
+        &[instructions::update_permissions(
+            &creator,
+            creator,
+            treasurer.pubkey(),
+            creator,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    process(
+        &mut pool.context,
+        &[instructions::propose_authority(
+            &creator,
+            &new_authority.pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        { global(&mut pool.context).await.update_authority },
+        creator
+    );
+
+    process(
+        &mut pool.context,
+        &[instructions::accept_authority(&new_authority.pubkey())],
+        &[&new_authority],
+    )
+    .await
+    .unwrap();
+    let global = global(&mut pool.context).await;
+    assert_eq!({ global.update_authority }, new_authority.pubkey());
+    assert_eq!({ global.pending_authority }, Default::default());
+    assert_eq!({ global.operator }, new_authority.pubkey());
+    assert_eq!({ global.pauser }, new_authority.pubkey());
+    assert_eq!({ global.treasurer }, treasurer.pubkey());
+
+    // The previous authority lost its rights, the roles it held included
+    let result = process(
+        &mut pool.context,
+        &[instructions::propose_authority(&creator, &creator)],
+        &[],
+    )
+    .await;
+    assert_error(result, ErrorCode::InvalidUpdateAuthority);
+    let result = process(&mut pool.context, &[instructions::pause(&creator)], &[]).await;
+    assert_error(result, ErrorCode::InvalidPauser);
+    let change = ConfigChange::UpdateChangeDelay { change_delay: 0 };
+    let result = process(
+        &mut pool.context,
+        &[instructions::queue_change(&creator, 0, change.clone())],
+        &[],
+    )
+    .await;
+    assert_error(result, ErrorCode::InvalidOperator);
+
+    process(
+        &mut pool.context,
+        &[
+            instructions::pause(&new_authority.pubkey()),
+            instructions::queue_change(&new_authority.pubkey(), 0, change),
+        ],
+        &[&new_authority],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn admin_instructions_reject_other_signers() {
+    let mut pool = start_pool().await;
+    let intruder = create_player(&mut pool.context).await;
+    let key = intruder.pubkey();
+
+    let result = process(
+        &mut pool.context,
+        &[instructions::propose_authority(&key, &key)],
+        &[&intruder],
+    )
+    .await;
+    assert_error(result, ErrorCode::InvalidUpdateAuthority);
+
+    let result = process(
+        &mut pool.context,
+        &[instructions::update_permissions(&key, key, key, key)],
+        &[&intruder],
+    )
+    .await;
+    assert_error(result, ErrorCode::InvalidUpdateAuthority);
+
+    let result = process(
+        &mut pool.context,
+        &[instructions::pause(&key)],
+        &[&intruder],
+    )
+    .await;
+    assert_error(result, ErrorCode::InvalidPauser);
+
+    let result = process(
+        &mut pool.context,
+        &[instructions::publish_role_merkle_root(&key, [0; 32])],
+        &[&intruder],
+    )
+    .await;
+    assert_error(result, ErrorCode::InvalidOperator);
+}
+
+#[tokio::test]
+async fn queued_change_waits_for_the_timelock() {
+    let mut pool = start_pool().await;
+    let operator = pool.context.payer.pubkey();
+    let change = ConfigChange::UpdateRole {
+        role_type: RoleType::Gorrila,
+        reward_boost_bps: 1_000,
+        odds_shift: 0,
+        jail_time_reduction: 0,
+    };
+
+    process(
+        &mut pool.context,
+        &[instructions::queue_change(&operator, 0, change)],
+        &[],
+    )
+    .await
+    .unwrap();
+    let result = process(
+        &mut pool.context,
+        &[instructions::execute_change(
+            &operator,
+            0,
+            &pool.reward_mint,
+        )],
+        &[],
+    )
+    .await;
+    assert_error(result, ErrorCode::ChangeTimelocked);
+
+    warp_forward(&mut pool.context, DEFAULT_CHANGE_DELAY as i64).await;
+    process(
+        &mut pool.context,
+        &[instructions::execute_change(
+            &operator,
+            0,
+            &pool.reward_mint,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let global = global(&mut pool.context).await;
+    assert_eq!({ global.role(RoleType::Gorrila).reward_boost_bps }, 1_000);
+}
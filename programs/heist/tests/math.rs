@@ -0,0 +1,144 @@
+use anchor_lang::prelude::Pubkey;
+use heist::economics::*;
+use heist::state::TOTAL_ODDS;
+use proptest::prelude::*;
+
+// Tables of 1 to 16 outcomes whose odds add up to TOTAL_ODDS
+fn table() -> impl Strategy<Value = Vec<u32>> {
+    prop::collection::vec(0..TOTAL_ODDS, 0..16).prop_map(|mut cuts| {
+        cuts.sort_unstable();
+        cuts.push(TOTAL_ODDS);
+        let mut previous = 0;
+        cuts.into_iter()
+            .map(|cut| {
+                let odds = cut - previous;
+                previous = cut;
+                odds
+            })
+            .collect()
+    })
+}
+
+const GENESIS: u64 = 1_700_000_000;
+const SEASON: u64 = 30 * 24 * 3600;
+
+fn owner() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+proptest! {
+    #[test]
+    fn reward_math_never_panics(
+        reward_per_hour: u64,
+        reward_boost_bps: u16,
+        rarity: u16,
+        stake_start_time: u64,
+        current_time: u64,
+        payout_multiplier: u8,
+    ) {
+        let elapsed = elapsed_since(stake_start_time, current_time);
+        let base = accrued_reward(reward_per_hour, reward_boost_bps, rarity, elapsed);
+        payout(base, payout_multiplier);
+    }
+
+    #[test]
+    fn reward_is_monotone_in_time(
+        reward_per_hour: u64,
+        reward_boost_bps: u16,
+        rarity: u16,
+        stake_start_time: u64,
+        first: u64,
+        second: u64,
+    ) {
+        let (earlier, later) = (first.min(second), first.max(second));
+        prop_assert!(
+            accrued_reward(reward_per_hour, reward_boost_bps, rarity, elapsed_since(stake_start_time, earlier))
+                <= accrued_reward(reward_per_hour, reward_boost_bps, rarity, elapsed_since(stake_start_time, later))
+        );
+    }
+
+    #[test]
+    fn committed_reward_covers_every_payout(
+        reward_per_hour in 0..1_000_000_000u64,
+        reward_boost_bps: u16,
+        payout_multiplier in 0..=10u8,
+        stakes in prop::collection::vec((any::<u16>(), 0..SEASON), 1..8),
+        settled_at: u64,
+    ) {
+        let end_date = GENESIS + SEASON;
+        let role_weight: u64 = stakes.iter().map(|&(rarity, _)| u64::from(rarity)).sum();
+        let role_weighted_start: u128 = stakes
+            .iter()
+            .map(|&(rarity, offset)| u128::from(rarity) * u128::from(GENESIS + offset))
+            .sum();
+        let paid: u128 = stakes
+            .iter()
+            .map(|&(rarity, offset)| {
+                let elapsed = elapsed_since(GENESIS + offset, settled_at.min(end_date));
+                let base = accrued_reward(reward_per_hour, reward_boost_bps, rarity, elapsed);
+                u128::from(payout(base, payout_multiplier))
+            })
+            .sum();
+
+        let committed = committed_reward(
+            reward_per_hour,
+            reward_boost_bps,
+            10,
+            role_weight,
+            role_weighted_start,
+            end_date,
+        );
+        prop_assert!(paid <= u128::from(committed));
+    }
+
+    #[test]
+    fn roll_stays_in_range(unix_timestamp: u64, owner in owner(), odds_shift: u32) {
+        let roll = roll(unix_timestamp, &owner);
+        prop_assert!(roll < u64::from(TOTAL_ODDS));
+        prop_assert!(shifted_roll(roll, odds_shift) <= roll);
+    }
+
+    #[test]
+    fn selection_is_total(odds in table(), roll: u64) {
+        let index = select_outcome(odds.iter().copied(), roll).unwrap();
+        prop_assert!(index < odds.len());
+
+        // Inside the table the roll falls in the selected slice
+        if roll < u64::from(TOTAL_ODDS) {
+            let start: u64 = odds[..index].iter().map(|&o| u64::from(o)).sum();
+            prop_assert!(start <= roll && roll < start + u64::from(odds[index]));
+        }
+    }
+
+    #[test]
+    fn empty_table_selects_nothing(roll: u64) {
+        prop_assert_eq!(select_outcome(std::iter::empty(), roll), None);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn rolls_follow_the_configured_odds(
+        odds in table(),
+        owner in owner(),
+        start in 0..u64::from(u32::MAX),
+    ) {
+        // One settlement per second over a little more than a day
+        const SAMPLES: u64 = TOTAL_ODDS as u64;
+        let mut hits = vec![0u64; odds.len()];
+        for unix_timestamp in start..start + SAMPLES {
+            let index = select_outcome(odds.iter().copied(), roll(unix_timestamp, &owner)).unwrap();
+            hits[index] += 1;
+        }
+
+        for (hits, &odds) in hits.iter().zip(&odds) {
+            let expected = u64::from(odds) * SAMPLES / u64::from(TOTAL_ODDS);
+            prop_assert!(
+                hits.abs_diff(expected) <= 1_500,
+                "{} hits for odds {}", hits, odds
+            );
+        }
+    }
+}
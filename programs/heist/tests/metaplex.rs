@@ -0,0 +1,94 @@
+mod common;
+
+use common::*;
+use heist::{error::ErrorCode, state::*};
+use heist_client::instructions;
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+use solana_sdk::signature::Signer;
+
+// Every account comes out of the Metaplex program rather than a fixture
+async fn start_metaplex_pool() -> Pool {
+    let mut context = start().await;
+    let creator = context.payer.pubkey();
+    let collection = mint_nft(&mut context, &creator, None, false).await;
+    let creator_nft = mint_nft(&mut context, &creator, Some(&collection), true).await;
+    open_pool_with(context, collection, creator_nft).await
+}
+
+#[tokio::test]
+async fn stakes_an_nft_metaplex_verified_into_the_collection() {
+    let mut pool = start_metaplex_pool().await;
+    let player = create_player(&mut pool.context).await;
+    let nft = mint_nft(
+        &mut pool.context,
+        &player.pubkey(),
+        Some(&pool.collection),
+        true,
+    )
+    .await;
+
+    let account = pool
+        .context
+        .banks_client
+        .get_account(nft.metadata)
+        .await
+        .unwrap()
+        .unwrap();
+    let metadata = Metadata::safe_deserialize(&account.data).unwrap();
+    let collection = metadata.collection.unwrap();
+    assert_eq!(collection.key, pool.collection.mint);
+    assert!(collection.verified);
+
+    stake(&mut pool, &player, &nft, BankTierRisk::Low)
+        .await
+        .unwrap();
+    assert_eq!(
+        {
+            global(&mut pool.context)
+                .await
+                .bank(BankTierRisk::Low)
+                .total_staked
+        },
+        1
+    );
+
+    warp_forward(&mut pool.context, 3600).await;
+    process(
+        &mut pool.context,
+        &[instructions::unstake_player_stake_info(
+            &player.pubkey(),
+            &nft.token_account,
+            &nft.mint,
+            &pool.collection.mint,
+            &pool.reward_mint,
+        )],
+        &[&player],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        {
+            global(&mut pool.context)
+                .await
+                .bank(BankTierRisk::Low)
+                .total_staked
+        },
+        0
+    );
+}
+
+#[tokio::test]
+async fn stake_rejects_an_nft_the_collection_never_verified() {
+    let mut pool = start_metaplex_pool().await;
+    let player = create_player(&mut pool.context).await;
+    let nft = mint_nft(
+        &mut pool.context,
+        &player.pubkey(),
+        Some(&pool.collection),
+        false,
+    )
+    .await;
+
+    let result = stake(&mut pool, &player, &nft, BankTierRisk::Low).await;
+    assert_error(result, ErrorCode::MismatchCollection);
+}
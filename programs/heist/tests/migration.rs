@@ -0,0 +1,169 @@
+mod common;
+
+use anchor_lang::{prelude::Pubkey, AccountSerialize};
+use common::*;
+use heist::{error::ErrorCode, state::*};
+use heist_client::{decode, instructions, pda::*};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    rent::Rent,
+    signature::{Keypair, Signer},
+};
+
+// Writes the stake the program used to create, keyed by token account and
+// delegated the NFT, in the allocation it used then
+async fn write_legacy_stake(pool: &mut Pool, player: &Keypair, nft: &Nft, role: RoleType) {
+    let legacy_stake =
+        find_legacy_player_stake_info_address(&player.pubkey(), &nft.token_account).0;
+    let mut data = Vec::new();
+    LegacyPlayerStakeInfo {
+        owner: player.pubkey(),
+        mint: nft.mint,
+        bank: BankTierRisk::Low,
+        stake_start_time: now(&mut pool.context).await as u64,
+        role,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    data.resize(LegacyPlayerStakeInfo::len(), 0);
+    let account = Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: heist::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    pool.context.set_account(&legacy_stake, &account.into());
+
+    process(
+        &mut pool.context,
+        &[spl_token::instruction::approve(
+            &spl_token::ID,
+            &nft.token_account,
+            &legacy_stake,
+            &player.pubkey(),
+            &[],
+            1,
+        )
+        .unwrap()],
+        &[player],
+    )
+    .await
+    .unwrap();
+}
+
+fn migrate(pool: &Pool, player: &Keypair, nft: &Nft) -> Instruction {
+    instructions::migrate_stake_info(
+        &player.pubkey(),
+        &nft.token_account,
+        &nft.mint,
+        &pool.reward_mint,
+    )
+}
+
+async fn account_exists(pool: &mut Pool, address: &Pubkey) -> bool {
+    pool.context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .is_some()
+}
+
+#[tokio::test]
+async fn migrates_a_stake_written_in_the_old_layout() {
+    let mut pool = start_pool().await;
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    write_legacy_stake(&mut pool, &player, &nft, RoleType::Gorrila).await;
+    let stake_start_time = now(&mut pool.context).await as u64;
+
+    let ix = migrate(&pool, &player, &nft);
+    process(&mut pool.context, &[ix], &[&player]).await.unwrap();
+
+    let stake_address = find_player_stake_info_address(&player.pubkey(), &nft.mint).0;
+    let account = pool
+        .context
+        .banks_client
+        .get_account(stake_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let stake = decode::player_stake_info(&account.data).unwrap();
+    assert_eq!(stake.mint, nft.mint);
+    assert!(matches!(stake.bank, BankTierRisk::Low));
+    assert!(matches!(stake.role, RoleType::Gorrila));
+    assert_eq!(stake.stake_start_time, stake_start_time);
+    assert_eq!(stake.rarity, BASE_RARITY_WEIGHT);
+    assert!(!stake.is_compressed);
+
+    let legacy_stake =
+        find_legacy_player_stake_info_address(&player.pubkey(), &nft.token_account).0;
+    assert!(!account_exists(&mut pool, &legacy_stake).await);
+    let nft_account = token_account(&mut pool.context, &nft.token_account).await;
+    assert_eq!(nft_account.delegate, Some(stake_address).into());
+    let global = global(&mut pool.context).await;
+    assert_eq!(
+        global.bank(BankTierRisk::Low).total_weight(),
+        u64::from(BASE_RARITY_WEIGHT)
+    );
+}
+
+#[tokio::test]
+async fn migration_waits_out_a_pause() {
+    let mut pool = start_pool().await;
+    let pauser = pool.context.payer.pubkey();
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    write_legacy_stake(&mut pool, &player, &nft, RoleType::Chimp).await;
+
+    process(&mut pool.context, &[instructions::pause(&pauser)], &[])
+        .await
+        .unwrap();
+    let ix = migrate(&pool, &player, &nft);
+    let result = process(&mut pool.context, &[ix.clone()], &[&player]).await;
+    assert_error(result, ErrorCode::ProgramPaused);
+
+    process(&mut pool.context, &[instructions::unpause(&pauser)], &[])
+        .await
+        .unwrap();
+    process(&mut pool.context, &[ix], &[&player]).await.unwrap();
+}
+
+#[tokio::test]
+async fn migration_respects_the_bank_role_list() {
+    let mut pool = start_pool().await;
+    let operator = pool.context.payer.pubkey();
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    write_legacy_stake(&mut pool, &player, &nft, RoleType::Chimp).await;
+
+    let change = ConfigChange::UpdateBankRoles {
+        bank_tier_risk: BankTierRisk::Low,
+        allowed_roles: vec![RoleType::Gorrila],
+    };
+    process(
+        &mut pool.context,
+        &[instructions::queue_change(&operator, 0, change)],
+        &[],
+    )
+    .await
+    .unwrap();
+    warp_forward(&mut pool.context, DEFAULT_CHANGE_DELAY as i64).await;
+    process(
+        &mut pool.context,
+        &[instructions::execute_change(
+            &operator,
+            0,
+            &pool.reward_mint,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let ix = migrate(&pool, &player, &nft);
+    let result = process(&mut pool.context, &[ix], &[&player]).await;
+    assert_error(result, ErrorCode::RoleNotAllowedInBank);
+}
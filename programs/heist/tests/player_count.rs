@@ -0,0 +1,56 @@
+mod common;
+
+use common::*;
+use heist::state::*;
+use heist_client::instructions;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn total_player_counts_players_with_a_live_stake() {
+    let mut pool = start_pool().await;
+    let authority = pool.context.payer.pubkey();
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    create_token_account(&mut pool.context, &player.pubkey(), &pool.reward_mint).await;
+    let force_unstake = instructions::force_unstake(
+        &authority,
+        &player.pubkey(),
+        &nft.mint,
+        &nft.token_account,
+        &pool.reward_mint,
+        true,
+    );
+
+    // A returning player used to be counted once and released twice
+    for _ in 0..2 {
+        stake(&mut pool, &player, &nft, BankTierRisk::Low)
+            .await
+            .unwrap();
+        assert_eq!({ global(&mut pool.context).await.total_player }, 1);
+        process(&mut pool.context, &[force_unstake.clone()], &[])
+            .await
+            .unwrap();
+        assert_eq!({ global(&mut pool.context).await.total_player }, 0);
+    }
+}
+
+#[tokio::test]
+async fn total_player_counts_a_player_once_across_stakes() {
+    let mut pool = start_pool().await;
+    let player = create_player(&mut pool.context).await;
+    let first = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    let second = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+
+    for nft in [&first, &second] {
+        stake(&mut pool, &player, nft, BankTierRisk::Low)
+            .await
+            .unwrap();
+        assert_eq!({ global(&mut pool.context).await.total_player }, 1);
+    }
+    assert_eq!(
+        player_info(&mut pool.context, &player.pubkey())
+            .await
+            .active_staked,
+        2
+    );
+}
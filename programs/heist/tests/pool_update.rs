@@ -0,0 +1,50 @@
+mod common;
+
+use common::*;
+use heist::error::ErrorCode;
+use heist_client::instructions;
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::Signer;
+
+async fn update_end_date(pool: &mut Pool, end_date: u64) -> Result<(), BanksClientError> {
+    let creator = pool.context.payer.pubkey();
+    process(
+        &mut pool.context,
+        &[instructions::init_or_update_collection(
+            &creator,
+            &pool.creator_nft.token_account,
+            &pool.creator_nft.mint,
+            &pool.collection.mint,
+            &pool.reward_mint,
+            end_date,
+            REWARD_PER_HOUR,
+        )],
+        &[],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn update_cannot_pull_the_end_date_in() {
+    let mut pool = start_pool().await;
+    let end_date = global(&mut pool.context).await.end_date;
+    let soon = now(&mut pool.context).await as u64 + 1;
+
+    for shorter in [end_date - 1, soon] {
+        let result = update_end_date(&mut pool, shorter).await;
+        assert_error(result, ErrorCode::EndDateShortened);
+    }
+    assert_eq!({ global(&mut pool.context).await.end_date }, end_date);
+}
+
+#[tokio::test]
+async fn update_can_push_the_end_date_out() {
+    let mut pool = start_pool().await;
+    let end_date = global(&mut pool.context).await.end_date;
+
+    update_end_date(&mut pool, end_date + 3600).await.unwrap();
+    assert_eq!(
+        { global(&mut pool.context).await.end_date },
+        end_date + 3600
+    );
+}
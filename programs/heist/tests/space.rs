@@ -0,0 +1,120 @@
+use anchor_lang::{prelude::*, Discriminator};
+use heist::state::*;
+
+fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    data.len()
+}
+
+// Global has no realloc path, every bank table is allocated at full capacity
+#[test]
+fn global_len_matches_zero_copy_layout() {
+    let mut global: Global = bytemuck::Zeroable::zeroed();
+    global.banks = Bank::generate_banks(100);
+    global.roles = Role::new();
+    let outcome = global.banks[2].bank_outcomes[0];
+    global.banks[2].set_outcomes(&[outcome; MAX_BANK_OUTCOMES]);
+
+    let mut data = Global::discriminator().to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&global));
+    assert_eq!(Global::len(), data.len());
+}
+
+#[test]
+fn default_tables_fit_bank_capacity() {
+    for risk in [BankTierRisk::Low, BankTierRisk::Mid, BankTierRisk::High] {
+        let bank_outcomes = BankOutcome::generate_bank(risk);
+        let bank = Bank::new(risk, 100, &bank_outcomes);
+
+        assert!(bank_outcomes.len() <= MAX_BANK_OUTCOMES);
+        assert_eq!(bank.outcomes().len(), bank_outcomes.len());
+    }
+}
+
+#[test]
+fn player_info_len_matches_serialized() {
+    let player_info = PlayerInfo {
+        is_initialized: true,
+        point_balance: u64::MAX,
+        active_staked: u16::MAX,
+        jail_end_time: u64::MAX,
+    };
+
+    assert_eq!(PlayerInfo::len(), serialized_len(&player_info));
+}
+
+#[test]
+fn player_stake_info_len_matches_serialized() {
+    let player_stake_info = PlayerStakeInfo {
+        owner: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        bank: BankTierRisk::High,
+        stake_start_time: u64::MAX,
+        role: RoleType::Gorrila,
+        is_compressed: true,
+        rarity: u16::MAX,
+    };
+
+    assert_eq!(PlayerStakeInfo::len(), serialized_len(&player_stake_info));
+}
+
+#[test]
+fn blocklist_len_holds_a_full_list() {
+    let blocklist = Blocklist {
+        wallets: (0..MAX_BLOCKED_WALLETS)
+            .map(|_| Pubkey::new_unique())
+            .collect(),
+    };
+
+    assert_eq!(Blocklist::len(), serialized_len(&blocklist));
+}
+
+#[test]
+fn pending_change_len_matches_serialized() {
+    let outcome = BankOutcomeArgs {
+        odds: TOTAL_ODDS,
+        payout_multiplier: u8::MAX,
+        is_negative: true,
+        negative_outcome: NegativeOutcome::Rekt,
+    };
+    let changes = [
+        ConfigChange::UpdateBank {
+            bank_tier_risk: BankTierRisk::High,
+            reward_per_hour: u64::MAX,
+            bank_outcomes: vec![outcome; MAX_BANK_OUTCOMES],
+        },
+        ConfigChange::UpdateBank {
+            bank_tier_risk: BankTierRisk::Low,
+            reward_per_hour: 0,
+            bank_outcomes: vec![],
+        },
+        ConfigChange::UpdateBankRoles {
+            bank_tier_risk: BankTierRisk::Mid,
+            allowed_roles: vec![RoleType::Chimp, RoleType::Gorrila],
+        },
+        ConfigChange::UpdateRole {
+            role_type: RoleType::Gorrila,
+            reward_boost_bps: u16::MAX,
+            odds_shift: u32::MAX,
+            jail_time_reduction: u64::MAX,
+        },
+        ConfigChange::UpdateChangeDelay {
+            change_delay: u64::MAX,
+        },
+        ConfigChange::UpdateRtpBounds {
+            min_rtp_bps: 0,
+            max_rtp_bps: u32::MAX,
+        },
+    ];
+
+    for change in changes {
+        let pending_change = PendingChange {
+            id: u64::MAX,
+            change: change.clone(),
+            queued_at: u64::MAX,
+            earliest_execution_time: u64::MAX,
+        };
+        assert_eq!(PendingChange::len(&change), serialized_len(&pending_change));
+    }
+}
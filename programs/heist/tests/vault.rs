@@ -0,0 +1,204 @@
+mod common;
+
+use anchor_lang::{
+    error::ErrorCode as AnchorErrorCode,
+    prelude::Pubkey,
+    solana_program::{program_pack::Pack, system_instruction},
+};
+use common::*;
+use heist::{error::ErrorCode, state::*};
+use heist_client::{instructions, pda::*};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+
+// A second pool owned reward account, off the associated token address
+async fn create_side_account(
+    context: &mut ProgramTestContext,
+    reward_mint: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let account = Keypair::new();
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    process(
+        context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::ID,
+                &account.pubkey(),
+                reward_mint,
+                &find_global_address().0,
+            )
+            .unwrap(),
+        ],
+        &[&account],
+    )
+    .await
+    .unwrap();
+    mint_to(context, reward_mint, &account.pubkey(), amount).await;
+    account.pubkey()
+}
+
+#[tokio::test]
+async fn rewards_only_move_through_the_vault() {
+    let mut pool = start_pool().await;
+    let authority = pool.context.payer.pubkey();
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    create_token_account(&mut pool.context, &player.pubkey(), &pool.reward_mint).await;
+    stake(&mut pool, &player, &nft, BankTierRisk::Low)
+        .await
+        .unwrap();
+
+    let vault = get_global_reward_token_address(&pool.reward_mint);
+    let side_account = create_side_account(&mut pool.context, &pool.reward_mint, 1_000).await;
+    let destination = get_player_reward_token_address(&authority, &pool.reward_mint);
+    let mut withdraw =
+        instructions::withdraw_treasury(&authority, &pool.reward_mint, &destination, 1_000);
+    let mut unstake = instructions::unstake_player_stake_info(
+        &player.pubkey(),
+        &nft.token_account,
+        &nft.mint,
+        &pool.collection.mint,
+        &pool.reward_mint,
+    );
+
+    // Solvency is measured on the vault, payouts can't come from elsewhere
+    for ix in [&mut withdraw, &mut unstake] {
+        for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == vault) {
+            meta.pubkey = side_account;
+        }
+    }
+    let result = process(&mut pool.context, &[withdraw], &[]).await;
+    assert_error(result, AnchorErrorCode::ConstraintAddress);
+    let result = process(&mut pool.context, &[unstake], &[&player]).await;
+    assert_error(result, AnchorErrorCode::ConstraintAddress);
+    assert_eq!(token_balance(&mut pool.context, &side_account).await, 1_000);
+}
+
+#[tokio::test]
+async fn vault_has_to_cover_live_stakes() {
+    let mut pool = start_pool().await;
+    let operator = pool.context.payer.pubkey();
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    stake(&mut pool, &player, &nft, BankTierRisk::Low)
+        .await
+        .unwrap();
+
+    // A rate the vault can't pay out for the rest of the season
+    let change = ConfigChange::UpdateBank {
+        bank_tier_risk: BankTierRisk::Low,
+        reward_per_hour: VAULT_TOP_UP,
+        bank_outcomes: vec![BankOutcomeArgs {
+            odds: TOTAL_ODDS,
+            payout_multiplier: 1,
+            is_negative: false,
+            negative_outcome: NegativeOutcome::None,
+        }],
+    };
+    process(
+        &mut pool.context,
+        &[instructions::queue_change(&operator, 0, change)],
+        &[],
+    )
+    .await
+    .unwrap();
+    warp_forward(&mut pool.context, DEFAULT_CHANGE_DELAY as i64).await;
+    let result = process(
+        &mut pool.context,
+        &[instructions::execute_change(
+            &operator,
+            0,
+            &pool.reward_mint,
+        )],
+        &[],
+    )
+    .await;
+    assert_error(result, ErrorCode::InsufficientVault);
+
+    // With the bank empty the change goes through, new stakes are turned away
+    process(
+        &mut pool.context,
+        &[instructions::unstake_player_stake_info(
+            &player.pubkey(),
+            &nft.token_account,
+            &nft.mint,
+            &pool.collection.mint,
+            &pool.reward_mint,
+        )],
+        &[&player],
+    )
+    .await
+    .unwrap();
+    process(
+        &mut pool.context,
+        &[instructions::execute_change(
+            &operator,
+            0,
+            &pool.reward_mint,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    // Another player, the unstake above may have jailed this one
+    let other_player = create_player(&mut pool.context).await;
+    let other = create_nft(
+        &mut pool.context,
+        &other_player.pubkey(),
+        Some(&pool.collection),
+    )
+    .await;
+    let result = stake(&mut pool, &other_player, &other, BankTierRisk::Low).await;
+    assert_error(result, ErrorCode::InsufficientVault);
+}
+
+#[tokio::test]
+async fn treasury_keeps_what_live_stakes_can_claim() {
+    let mut pool = start_pool().await;
+    let treasurer = pool.context.payer.pubkey();
+    let player = create_player(&mut pool.context).await;
+    let nft = create_nft(&mut pool.context, &player.pubkey(), Some(&pool.collection)).await;
+    stake(&mut pool, &player, &nft, BankTierRisk::Low)
+        .await
+        .unwrap();
+
+    let vault = get_global_reward_token_address(&pool.reward_mint);
+    let balance = token_balance(&mut pool.context, &vault).await;
+    let committed = global(&mut pool.context).await.committed_rewards();
+    let destination = get_player_reward_token_address(&treasurer, &pool.reward_mint);
+    let result = process(
+        &mut pool.context,
+        &[instructions::withdraw_treasury(
+            &treasurer,
+            &pool.reward_mint,
+            &destination,
+            balance - committed + 1,
+        )],
+        &[],
+    )
+    .await;
+    assert_error(result, ErrorCode::InsufficientVault);
+
+    process(
+        &mut pool.context,
+        &[instructions::withdraw_treasury(
+            &treasurer,
+            &pool.reward_mint,
+            &destination,
+            balance - committed,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut pool.context, &vault).await, committed);
+}